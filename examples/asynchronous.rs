@@ -1,8 +1,10 @@
+#![allow(clippy::await_holding_lock, clippy::useless_format)]
+
 use std::sync::{Arc, LazyLock, Mutex};
 use std::time::Duration;
 use whoopsie::backoff::BackoffBuilder;
 use whoopsie::circuit_breaker::CircuitBreakerBuilder;
-use whoopsie::error::Error;
+use whoopsie::error::{Error, ErrorKind};
 
 static ATTEMPTS: LazyLock<Arc<Mutex<usize>>> = LazyLock::new(|| Arc::new(Mutex::new(0)));
 
@@ -19,9 +21,7 @@ async fn brewing_coffee() -> Result<&'static str, Error>{
     ];
 
     match failure_scenarios.get(*attempts % failure_scenarios.len()) {
-        Some(Some(error_message)) => Err(Error {
-            description: error_message.to_string(),
-        }),
+        Some(Some(error_message)) => Err(Error::new(ErrorKind::Operation, error_message.to_string())),
         _ => {
             // Need time to pour coffee into coffee cup.
             tokio::time::sleep(Duration::from_millis(100)).await;
@@ -1,7 +1,7 @@
 use rand::Rng;
 use std::time::Duration;
 use whoopsie::backoff::BackoffBuilder;
-use whoopsie::error::Error;
+use whoopsie::error::{Error, ErrorKind};
 
 fn main() {
     let mut backoff = BackoffBuilder::new()
@@ -22,13 +22,9 @@ fn main() {
         if chance < 0.3 {
             Ok("🌎 Liftoff successful! Next stop: Mars!")
         } else if chance < 0.6 {
-            Err(Error {
-                description: "🛠️ Engine failure detected!".to_string(),
-            })
+            Err(Error::new(ErrorKind::Operation, "🛠️ Engine failure detected!".to_string()))
         } else {
-            Err(Error {
-                description: "🌩️ Bad weather conditions, launch aborted!".to_string(),
-            })
+            Err(Error::new(ErrorKind::Operation, "🌩️ Bad weather conditions, launch aborted!".to_string()))
         }
     };
 
@@ -1,7 +1,7 @@
 use rand::Rng;
 use std::time::Duration;
 use whoopsie::backoff::BackoffBuilder;
-use whoopsie::error::Error;
+use whoopsie::error::{Error, ErrorKind};
 
 fn main() {
     let mut backoff = BackoffBuilder::new()
@@ -16,15 +16,9 @@ fn main() {
         let number = rng.random_range(0..=4);
         match number {
             0 | 1 => Ok("Rust is the best! Memory safety and zero-cost abstractions!"),
-            2 => Err(Error {
-                description: "C++ enters the chat: 'Did someone say performance?'".to_string(),
-            }),
-            3 => Err(Error {
-                description: "Python interrupts: 'But readability matters!'".to_string(),
-            }),
-            _ => Err(Error {
-                description: "C# says: 'Hey, have you tried .NET? It's pretty cool too!'".to_string(),
-            }),
+            2 => Err(Error::new(ErrorKind::Operation, "C++ enters the chat: 'Did someone say performance?'".to_string())),
+            3 => Err(Error::new(ErrorKind::Operation, "Python interrupts: 'But readability matters!'".to_string())),
+            _ => Err(Error::new(ErrorKind::Operation, "C# says: 'Hey, have you tried .NET? It's pretty cool too!'".to_string())),
         }
     };
 
@@ -1,7 +1,9 @@
+#![allow(clippy::unnecessary_unwrap)]
+
 use std::time::Duration;
 use whoopsie::backoff::BackoffBuilder;
 use whoopsie::circuit_breaker::CircuitBreakerBuilder;
-use whoopsie::error::Error;
+use whoopsie::error::{Error, ErrorKind};
 
 fn main() {
     let backoff = BackoffBuilder::new()
@@ -25,9 +27,7 @@ fn main() {
         else {
             Err(response.error_for_status()
                 .map_err(|error| {
-                    Error {
-                        description: error.to_string(),
-                    }
+                    Error::new(ErrorKind::Operation, error.to_string())
                 })
                 .unwrap_err()
             )
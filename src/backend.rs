@@ -0,0 +1,351 @@
+use crate::circuit_breaker::State;
+use crate::error::{Error, ErrorKind};
+use crate::policy::BoxFuture;
+use crate::snapshot::CircuitBreakerSnapshot;
+use crate::state_store::{StateStore, Update};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::runtime::Handle;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionedState {
+    pub state: State,
+    pub version: u64,
+}
+
+pub trait StateBackend: Send + Sync {
+    fn get_state<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<VersionedState>, Error>>;
+
+    fn compare_and_set_state<'a>(
+        &'a self,
+        key: &'a str,
+        expected_version: Option<u64>,
+        state: State,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, Result<Option<u64>, Error>>;
+
+    fn increment<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<u64, Error>>;
+
+    fn count<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<u64, Error>>;
+}
+
+pub fn window_index(window: Duration) -> u128 {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+    since_epoch.as_nanos() / window.as_nanos().max(1)
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    value: T,
+    expires_at: Option<Instant>,
+}
+
+impl<T> Entry<T> {
+    fn is_live(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    states: HashMap<String, Entry<VersionedState>>,
+    counters: HashMap<(String, u128), Entry<u64>>,
+    version: u64,
+}
+
+impl Entries {
+    fn evict_expired(&mut self, now: Instant) {
+        self.states.retain(|_, entry| entry.is_live(now));
+        self.counters.retain(|_, entry| entry.is_live(now));
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryStateBackend {
+    entries: Mutex<Entries>,
+}
+
+impl InMemoryStateBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.evict_expired(Instant::now());
+        entries
+    }
+}
+
+impl StateBackend for InMemoryStateBackend {
+    fn get_state<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<VersionedState>, Error>> {
+        Box::pin(async move {
+            Ok(self.entries().states.get(key).map(|entry| entry.value.clone()))
+        })
+    }
+
+    fn compare_and_set_state<'a>(
+        &'a self,
+        key: &'a str,
+        expected_version: Option<u64>,
+        state: State,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, Result<Option<u64>, Error>> {
+        Box::pin(async move {
+            let mut entries = self.entries();
+            let current_version = entries.states.get(key).map(|entry| entry.value.version);
+            if current_version != expected_version {
+                return Ok(None);
+            }
+            entries.version += 1;
+            let version = entries.version;
+            entries.states.insert(key.to_string(), Entry {
+                value: VersionedState {
+                    state,
+                    version,
+                },
+                expires_at: ttl.map(|ttl| Instant::now() + ttl),
+            });
+            Ok(Some(version))
+        })
+    }
+
+    fn increment<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<u64, Error>> {
+        Box::pin(async move {
+            let mut entries = self.entries();
+            let entry = entries.counters
+                .entry((key.to_string(), window_index(window)))
+                .or_insert_with(|| Entry {
+                    value: 0,
+                    expires_at: Some(Instant::now() + window),
+                });
+            entry.value += 1;
+            Ok(entry.value)
+        })
+    }
+
+    fn count<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<u64, Error>> {
+        Box::pin(async move {
+            let entries = self.entries();
+            Ok(entries.counters
+                .get(&(key.to_string(), window_index(window)))
+                .map(|entry| entry.value)
+                .unwrap_or(0))
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct BackendStateStore {
+    backend: Arc<dyn StateBackend>,
+    key: String,
+    runtime: Handle,
+    failure_window: Duration,
+    snapshot: Arc<Mutex<Option<CircuitBreakerSnapshot>>>,
+    opened: Arc<Mutex<Option<(u64, Instant)>>>,
+}
+
+impl BackendStateStore {
+    pub fn new(backend: Arc<dyn StateBackend>, key: &str, runtime: Handle) -> Self {
+        Self {
+            backend,
+            key: key.to_string(),
+            runtime,
+            failure_window: Duration::from_secs(60),
+            snapshot: Arc::new(Mutex::new(None)),
+            opened: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn with_failure_window(&mut self, window: Duration) -> &mut Self {
+        self.failure_window = window;
+        self
+    }
+
+    fn resets_key(&self) -> String {
+        format!("{}:resets", self.key)
+    }
+
+    fn failures_key(&self, generation: u64) -> String {
+        format!("{}:failures:{}", self.key, generation)
+    }
+
+    fn failures(&self) -> Result<(u64, usize), Error> {
+        let generation = self.block_on(self.backend.count(&self.resets_key(), self.failure_window))?;
+        let failures = self.block_on(self.backend.count(&self.failures_key(generation), self.failure_window))?;
+        Ok((generation, failures as usize))
+    }
+
+    fn block_on<T: Send>(&self, future: BoxFuture<'_, Result<T, Error>>) -> Result<T, Error> {
+        if Handle::try_current().is_err() {
+            return self.runtime.block_on(future);
+        }
+        std::thread::scope(|scope| scope.spawn(|| self.runtime.block_on(future)).join())
+            .unwrap_or_else(|_| Err(Error::new(
+                ErrorKind::StateStore,
+                format!("State backend '{}' panicked", self.key),
+            )))
+    }
+
+    fn opened_at(&self, state: &VersionedState) -> Option<Instant> {
+        let mut opened = self.opened.lock().unwrap();
+        if state.state != State::Open {
+            *opened = None;
+            return None;
+        }
+        match *opened {
+            Some((version, opened_at)) if version == state.version => Some(opened_at),
+            _ => {
+                let opened_at = Instant::now();
+                *opened = Some((state.version, opened_at));
+                Some(opened_at)
+            }
+        }
+    }
+
+    fn shared(&self, state: Option<&VersionedState>, failures: usize) -> Option<CircuitBreakerSnapshot> {
+        let local = self.snapshot.lock().unwrap().clone();
+        if state.is_none() && local.is_none() && failures == 0 {
+            return None;
+        }
+        let mut snapshot = local.unwrap_or_else(|| CircuitBreakerSnapshot {
+            taken_at: SystemTime::now(),
+            state: State::Closed,
+            manual_override: None,
+            failed_attempts: 0,
+            failure_count: 0,
+            open_for: None,
+            retry_budget: Vec::new(),
+        });
+        snapshot.refresh();
+        snapshot.failure_count = failures;
+        if let Some(state) = state {
+            snapshot.set_state(state.state.clone());
+            if let Some(opened_at) = self.opened_at(state) {
+                snapshot.open_for = snapshot.open_for.max(Some(opened_at.elapsed()));
+            }
+        }
+        Some(snapshot)
+    }
+}
+
+impl Debug for BackendStateStore {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.debug_struct("BackendStateStore")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl StateStore for BackendStateStore {
+    fn load(&self) -> Result<Option<CircuitBreakerSnapshot>, Error> {
+        let state = self.block_on(self.backend.get_state(&self.key))?;
+        let (_, failures) = self.failures()?;
+        Ok(self.shared(state.as_ref(), failures))
+    }
+
+    fn update(&self, update: &mut Update<'_>) -> Result<CircuitBreakerSnapshot, Error> {
+        loop {
+            let current = self.block_on(self.backend.get_state(&self.key))?;
+            let (generation, failures) = self.failures()?;
+            let snapshot = update(self.shared(current.as_ref(), failures));
+            let version = current.map(|current| current.version);
+            let stored = self.block_on(
+                self.backend.compare_and_set_state(&self.key, version, snapshot.state.clone(), None)
+            )?;
+            if stored.is_some() {
+                if snapshot.failure_count < failures {
+                    self.block_on(self.backend.increment(&self.resets_key(), self.failure_window))?;
+                }
+                for _ in failures..snapshot.failure_count {
+                    self.block_on(self.backend.increment(&self.failures_key(generation), self.failure_window))?;
+                }
+                *self.snapshot.lock().unwrap() = Some(snapshot.clone());
+                return Ok(snapshot);
+            }
+        }
+    }
+}
+
+pub mod conformance {
+    use super::StateBackend;
+    use crate::circuit_breaker::State;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    const TTL: Duration = Duration::from_millis(100);
+
+    fn key(check: &str) -> String {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_nanos();
+        format!("whoopsie:conformance:{}:{}", check, nanos)
+    }
+
+    pub async fn verify<B: StateBackend + ?Sized>(backend: &B) {
+        missing_state_is_absent(backend).await;
+        compare_and_set_creates_absent_state(backend).await;
+        compare_and_set_requires_current_version(backend).await;
+        states_expire_after_ttl(backend).await;
+        counters_accumulate_per_key(backend).await;
+        counters_reset_after_window(backend).await;
+    }
+
+    pub async fn missing_state_is_absent<B: StateBackend + ?Sized>(backend: &B) {
+        let key = key("missing");
+        assert_eq!(None, backend.get_state(&key).await.unwrap(), "Missing key must have no state");
+    }
+
+    pub async fn compare_and_set_creates_absent_state<B: StateBackend + ?Sized>(backend: &B) {
+        let key = key("create");
+        let version = backend.compare_and_set_state(&key, None, State::Open, None).await.unwrap()
+            .expect("Setting an absent state without a version must succeed");
+        let current = backend.get_state(&key).await.unwrap().expect("State must be stored");
+        assert_eq!(State::Open, current.state);
+        assert_eq!(version, current.version);
+        let conflict = backend.compare_and_set_state(&key, None, State::Closed, None).await.unwrap();
+        assert_eq!(None, conflict, "Setting an existing state without a version must conflict");
+    }
+
+    pub async fn compare_and_set_requires_current_version<B: StateBackend + ?Sized>(backend: &B) {
+        let key = key("version");
+        let first = backend.compare_and_set_state(&key, None, State::Closed, None).await.unwrap().unwrap();
+        let second = backend.compare_and_set_state(&key, Some(first), State::Open, None).await.unwrap()
+            .expect("Setting with the current version must succeed");
+        assert_ne!(first, second, "Each update must produce a new version");
+        let stale = backend.compare_and_set_state(&key, Some(first), State::HalfOpen, None).await.unwrap();
+        assert_eq!(None, stale, "Setting with a stale version must conflict");
+        let current = backend.get_state(&key).await.unwrap().unwrap();
+        assert_eq!(State::Open, current.state);
+        assert_eq!(second, current.version);
+    }
+
+    pub async fn states_expire_after_ttl<B: StateBackend + ?Sized>(backend: &B) {
+        let key = key("expire");
+        backend.compare_and_set_state(&key, None, State::Open, Some(TTL)).await.unwrap().unwrap();
+        assert!(backend.get_state(&key).await.unwrap().is_some(), "State must live until its TTL");
+        tokio::time::sleep(TTL * 2).await;
+        assert_eq!(None, backend.get_state(&key).await.unwrap(), "State must expire after its TTL");
+        let recreated = backend.compare_and_set_state(&key, None, State::Closed, None).await.unwrap();
+        assert!(recreated.is_some(), "Expired state must be treated as absent");
+    }
+
+    pub async fn counters_accumulate_per_key<B: StateBackend + ?Sized>(backend: &B) {
+        let first = key("counter-first");
+        let second = key("counter-second");
+        let window = Duration::from_secs(100 * 365 * 24 * 3600);
+        assert_eq!(0, backend.count(&first, window).await.unwrap());
+        assert_eq!(1, backend.increment(&first, window).await.unwrap());
+        assert_eq!(2, backend.increment(&first, window).await.unwrap());
+        assert_eq!(1, backend.increment(&second, window).await.unwrap());
+        assert_eq!(2, backend.count(&first, window).await.unwrap());
+        assert_eq!(1, backend.count(&second, window).await.unwrap());
+    }
+
+    pub async fn counters_reset_after_window<B: StateBackend + ?Sized>(backend: &B) {
+        let key = key("window");
+        backend.increment(&key, TTL).await.unwrap();
+        tokio::time::sleep(TTL * 2).await;
+        assert_eq!(0, backend.count(&key, TTL).await.unwrap(), "Counters must reset once their window has passed");
+        assert_eq!(1, backend.increment(&key, TTL).await.unwrap());
+    }
+}
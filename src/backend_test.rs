@@ -0,0 +1,171 @@
+use crate::backend::{conformance, BackendStateStore, InMemoryStateBackend, StateBackend, VersionedState};
+use crate::backoff::WaitStrategy;
+use crate::circuit_breaker::{CircuitBreaker, State};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{backoff_builder, circuit_breaker_builder, success_operation_async, TIME};
+use crate::policy::{AsyncPipelineBuilder, BoxFuture};
+use rstest::rstest;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::{Handle, Runtime};
+
+struct UnversionedBackend(InMemoryStateBackend);
+
+impl StateBackend for UnversionedBackend {
+    fn get_state<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<VersionedState>, Error>> {
+        self.0.get_state(key)
+    }
+
+    fn compare_and_set_state<'a>(
+        &'a self,
+        key: &'a str,
+        _: Option<u64>,
+        state: State,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, Result<Option<u64>, Error>> {
+        Box::pin(async move {
+            let current = self.0.get_state(key).await?.map(|current| current.version);
+            self.0.compare_and_set_state(key, current, state, ttl).await
+        })
+    }
+
+    fn increment<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<u64, Error>> {
+        self.0.increment(key, window)
+    }
+
+    fn count<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<u64, Error>> {
+        self.0.count(key, window)
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn in_memory_backend_conforms() {
+    conformance::verify(&InMemoryStateBackend::new()).await;
+}
+
+#[rstest]
+#[tokio::test]
+async fn in_memory_backend_conforms_as_trait_object() {
+    let backend: Box<dyn StateBackend> = Box::new(InMemoryStateBackend::new());
+    conformance::verify(backend.as_ref()).await;
+}
+
+#[rstest]
+#[tokio::test]
+#[should_panic(expected = "Setting an existing state without a version must conflict")]
+async fn conformance_catches_lost_updates() {
+    conformance::verify(&UnversionedBackend(InMemoryStateBackend::new())).await;
+}
+
+fn shared_circuit_breaker(state_store: BackendStateStore, wait_strategy: WaitStrategy) -> CircuitBreaker {
+    circuit_breaker_builder(1, 2, backoff_builder(TIME / 10, 1, wait_strategy))
+        .with_state_store(Arc::new(state_store))
+        .build()
+        .unwrap()
+}
+
+#[rstest]
+fn backend_state_store_shares_state() {
+    let runtime = Runtime::new().unwrap();
+    let backend: Arc<dyn StateBackend> = Arc::new(InMemoryStateBackend::new());
+    let state_store = BackendStateStore::new(backend.clone(), "payments-api", runtime.handle().clone());
+    let mut first = shared_circuit_breaker(state_store.clone(), WaitStrategy::Synchronous);
+    let mut second = shared_circuit_breaker(state_store, WaitStrategy::Synchronous);
+    first.record_failure();
+    first.record_failure();
+    assert_eq!(State::Open, *first.state());
+    let error = second.retry(&mut || Ok::<(), Error>(())).err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    let shared = runtime.block_on(backend.get_state("payments-api")).unwrap().unwrap();
+    assert_eq!(State::Open, shared.state);
+}
+
+#[rstest]
+#[tokio::test]
+async fn backend_state_store_shares_state_asynchronously(
+    mut success_operation_async: impl FnMut() -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static>>,
+) {
+    let backend: Arc<dyn StateBackend> = Arc::new(InMemoryStateBackend::new());
+    let first = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", Handle::current()),
+        WaitStrategy::Asynchronous,
+    );
+    let mut second = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", Handle::current()),
+        WaitStrategy::Asynchronous,
+    );
+    let mut pipeline = AsyncPipelineBuilder::new()
+        .with_policy(first)
+        .build();
+    for _ in 0..2 {
+        let result = pipeline.execute_async(&mut || async {
+            Err::<(), _>(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
+        }).await;
+        assert!(result.is_err());
+    }
+    assert_eq!(State::Open, backend.get_state("payments-api").await.unwrap().unwrap().state);
+    let error = second.retry_async(&mut success_operation_async).await.err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+}
+
+#[rstest]
+#[tokio::test]
+async fn backend_state_store_serves_synchronous_calls_inside_runtime() {
+    let backend: Arc<dyn StateBackend> = Arc::new(InMemoryStateBackend::new());
+    let mut circuit_breaker = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", Handle::current()),
+        WaitStrategy::Synchronous,
+    );
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    assert_eq!(State::Open, *circuit_breaker.state());
+    assert_eq!(State::Open, backend.get_state("payments-api").await.unwrap().unwrap().state);
+}
+
+#[rstest]
+fn backend_state_store_expires_shared_open_state() {
+    let runtime = Runtime::new().unwrap();
+    let backend: Arc<dyn StateBackend> = Arc::new(InMemoryStateBackend::new());
+    let mut first = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", runtime.handle().clone()),
+        WaitStrategy::Synchronous,
+    );
+    let mut second = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", runtime.handle().clone()),
+        WaitStrategy::Synchronous,
+    );
+    first.record_failure();
+    first.record_failure();
+    let error = second.retry(&mut || Ok::<(), Error>(())).err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    std::thread::sleep(TIME * 2);
+    assert!(second.retry(&mut || Ok::<(), Error>(())).is_ok());
+    assert_eq!(State::Closed, *second.state());
+    let shared = runtime.block_on(backend.get_state("payments-api")).unwrap().unwrap();
+    assert_eq!(State::Closed, shared.state);
+}
+
+#[rstest]
+fn backend_state_store_shares_failure_counts() {
+    let runtime = Runtime::new().unwrap();
+    let backend: Arc<dyn StateBackend> = Arc::new(InMemoryStateBackend::new());
+    let mut first = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", runtime.handle().clone()),
+        WaitStrategy::Synchronous,
+    );
+    let mut second = shared_circuit_breaker(
+        BackendStateStore::new(backend.clone(), "payments-api", runtime.handle().clone()),
+        WaitStrategy::Synchronous,
+    );
+    first.record_failure();
+    first.record_success();
+    second.record_failure();
+    assert_eq!(State::Closed, *second.state());
+    first.record_failure();
+    assert_eq!(State::Open, *first.state());
+    let error = second.retry(&mut || Ok::<(), Error>(())).err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+}
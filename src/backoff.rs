@@ -1,6 +1,17 @@
-use crate::error::Error;
+use crate::context::Context;
+use crate::{dsl, duration};
+use crate::error::{Error, ErrorKind, PollError};
+#[cfg(feature = "async")]
+use crate::policy::NextAsync;
+use crate::retry_budget::RetryBudget;
+use crate::telemetry;
+use std::collections::hash_map::RandomState;
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "async")]
 use std::future::Future;
-use std::time::Duration;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug, Clone)]
 pub(crate) enum TimeStrategy {
@@ -22,6 +33,39 @@ pub(crate) enum WaitStrategy {
     SpinLoop
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Jitter {
+    #[default]
+    None,
+    Full,
+    Equal,
+    Proportional(f32),
+}
+
+impl Jitter {
+    fn apply(&self, duration: Duration) -> Duration {
+        match self {
+            Jitter::None => duration,
+            Jitter::Full => duration.mul_f64(random_fraction()),
+            Jitter::Equal => duration / 2 + (duration / 2).mul_f64(random_fraction()),
+            Jitter::Proportional(ratio) => {
+                let ratio = *ratio as f64;
+                duration.mul_f64(1.0 - ratio + 2.0 * ratio * random_fraction())
+            }
+        }
+    }
+}
+
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(now.as_nanos());
+    }
+    hasher.finish() as f64 / u64::MAX as f64
+}
+
 impl WaitStrategy {
     pub fn synchronous_wait(&self, duration: &Duration) {
         std::thread::sleep(*duration);
@@ -38,6 +82,33 @@ impl WaitStrategy {
             std::hint::spin_loop();
         }
     }
+
+    pub fn wait(&self, duration: &Duration) {
+        match self {
+            WaitStrategy::Synchronous => {
+                self.synchronous_wait(duration);
+            }
+            #[cfg(feature = "async")]
+            WaitStrategy::Asynchronous => {
+                panic!("Asynchronous wait not supported in synchronous context");
+            }
+            WaitStrategy::SpinLoop => {
+                self.spin_loop_wait(duration);
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn wait_async(&self, duration: &Duration) {
+        match self {
+            WaitStrategy::Asynchronous => {
+                self.asynchronous_wait(duration).await
+            }
+            WaitStrategy::Synchronous | WaitStrategy::SpinLoop => {
+                panic!("Synchronous wait not supported in asynchronous context");
+            }
+        }
+    }
 }
 
 impl TimeStrategy {
@@ -51,9 +122,20 @@ impl TimeStrategy {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct BackoffBuilder {
     time_strategy: Option<TimeStrategy>,
-    wait_strategy: Option<WaitStrategy>
+    wait_strategy: Option<WaitStrategy>,
+    attempts: Option<usize>,
+    retry_budget: Option<RetryBudget>,
+    max_time: Option<Duration>,
+    jitter: Jitter,
+}
+
+impl Default for BackoffBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BackoffBuilder {
@@ -61,6 +143,10 @@ impl BackoffBuilder {
         Self {
             time_strategy: None,
             wait_strategy: None,
+            attempts: None,
+            retry_budget: None,
+            max_time: None,
+            jitter: Jitter::None,
         }
     }
 
@@ -80,6 +166,26 @@ impl BackoffBuilder {
         self
     }
 
+    pub fn with_attempts(&mut self, attempts: usize) -> &mut Self {
+        self.attempts = Some(attempts);
+        self
+    }
+
+    pub fn with_retry_budget(&mut self, retry_budget: RetryBudget) -> &mut Self {
+        self.retry_budget = Some(retry_budget);
+        self
+    }
+
+    pub fn with_max_time(&mut self, duration: Duration) -> &mut Self {
+        self.max_time = Some(duration);
+        self
+    }
+
+    pub fn with_jitter(&mut self, jitter: Jitter) -> &mut Self {
+        self.jitter = jitter;
+        self
+    }
+
     pub fn as_synchronous(&mut self) -> &mut Self {
         self.wait_strategy = Some(WaitStrategy::Synchronous);
         self
@@ -96,20 +202,39 @@ impl BackoffBuilder {
         self
     }
 
+    pub(crate) fn isolated(&self) -> BackoffBuilder {
+        let mut builder = self.clone();
+        builder.retry_budget = self.retry_budget.as_ref().map(RetryBudget::isolated);
+        builder
+    }
+
     pub fn build(&self) -> Result<Backoff, Error> {
         if self.time_strategy.is_none() {
-            return Err(Error {
-                description: "Time strategy is required. Call one of 'with_*' method".to_string(),
-            })
+            return Err(Error::new(ErrorKind::Configuration, "Time strategy is required. Call one of 'with_*' method".to_string()))
         }
         if self.wait_strategy.is_none() {
-            return Err(Error {
-                description: "Wait strategy is required. Call one of 'as_*' method".to_string(),
-            })
+            return Err(Error::new(ErrorKind::Configuration, "Wait strategy is required. Call one of 'as_*' method".to_string()))
+        }
+        if self.attempts == Some(0) {
+            return Err(Error::new(ErrorKind::Configuration, "Attempts must be greater than zero".to_string()))
+        }
+        if let Some(TimeStrategy::Exponential { factor, .. }) = &self.time_strategy {
+            if *factor <= 0.0 {
+                return Err(Error::new(ErrorKind::Configuration, "Factor must be greater than zero".to_string()))
+            }
+        }
+        if let Jitter::Proportional(ratio) = self.jitter {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::new(ErrorKind::Configuration, "Jitter ratio must be between 0.0 and 1.0".to_string()))
+            }
         }
         Ok(Backoff::new(
             self.time_strategy.as_ref().unwrap().clone(),
-            self.wait_strategy.as_ref().unwrap().clone()
+            self.wait_strategy.as_ref().unwrap().clone(),
+            self.attempts.unwrap_or(1),
+            self.retry_budget.clone(),
+            self.max_time,
+            self.jitter,
         ))
     }
 }
@@ -118,21 +243,39 @@ impl BackoffBuilder {
 pub struct Backoff {
     pub(crate) time_strategy: TimeStrategy,
     pub(crate) wait_strategy: WaitStrategy,
+    pub(crate) attempts: usize,
+    pub(crate) retry_budget: Option<RetryBudget>,
+    max_time: Option<Duration>,
+    jitter: Jitter,
+    attempt: usize,
+    started_at: Option<Instant>,
 }
 
 impl Backoff {
     fn new(
         time_strategy: TimeStrategy,
         wait_strategy: WaitStrategy,
+        attempts: usize,
+        retry_budget: Option<RetryBudget>,
+        max_time: Option<Duration>,
+        jitter: Jitter,
     ) -> Self {
         Self {
             time_strategy,
             wait_strategy,
+            attempts,
+            retry_budget,
+            max_time,
+            jitter,
+            attempt: 0,
+            started_at: None,
         }
     }
 
     pub fn reset(&mut self) {
         self.time_strategy.reset();
+        self.attempt = 0;
+        self.started_at = None;
     }
 
     pub fn retry<F, O, E>(&mut self, operation: &mut F) -> Result<O, E>
@@ -140,14 +283,26 @@ impl Backoff {
         F: FnMut() -> Result<O, E>,
         E: std::error::Error,
     {
-        let error = match operation() {
-            Ok(value) => return Ok(value),
-            Err(value) => value
-        };
-        let duration = Self::get_next(&self.time_strategy);
-        self.synchronous_wait(duration);
-        Self::compute_next(&mut self.time_strategy);
-        Err(error)
+        self.retry_with_context(&mut |_: &Context<E>| operation())
+    }
+
+    pub fn retry_with_context<F, O, E>(&mut self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        E: std::error::Error,
+    {
+        self.run(operation, &mut |result| result)
+    }
+
+    pub fn poll_until<F, P, O, E>(&mut self, operation: &mut F, predicate: &mut P) -> Result<O, PollError<O, E>>
+    where
+        F: FnMut() -> Result<O, E>,
+        P: FnMut(&O) -> bool,
+        E: std::error::Error,
+    {
+        self.run(&mut |_: &Context<PollError<O, E>>| operation().map_err(PollError::Failed), &mut |result| {
+            Self::classify_poll(result, predicate)
+        })
     }
 
     #[cfg(feature = "async")]
@@ -157,14 +312,292 @@ impl Backoff {
         E: std::error::Error,
         R: Future<Output = Result<O, E>>,
     {
-        let error = match operation().await {
-            Ok(value) => return Ok(value),
-            Err(value) => value
+        self.retry_with_context_async(&mut |_: &Context<E>| operation()).await
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn retry_with_context_async<F, O, E, R>(&mut self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> R,
+        E: std::error::Error,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.run_async(operation, &mut |result| result).await
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn poll_until_async<F, P, O, E, R>(
+        &mut self,
+        operation: &mut F,
+        predicate: &mut P,
+    ) -> Result<O, PollError<O, E>>
+    where
+        F: FnMut() -> R,
+        P: FnMut(&O) -> bool,
+        E: std::error::Error,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.run_async(&mut |_: &Context<PollError<O, E>>| {
+            let future = operation();
+            async move { future.await.map_err(PollError::Failed) }
+        }, &mut |result| {
+            Self::classify_poll(result, predicate)
+        }).await
+    }
+
+    fn run<F, C, O, E>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error,
+    {
+        let _span = telemetry::backoff_span(self.attempts).entered();
+        let started_at = Instant::now();
+        telemetry::call_started(None);
+        let result = self.run_attempts(operation, classify);
+        telemetry::call_finished(None, started_at.elapsed());
+        result
+    }
+
+    fn run_attempts<F, C, O, E>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error,
+    {
+        if let Some(retry_budget) = &self.retry_budget {
+            retry_budget.deposit();
+        }
+        let mut last_error: Option<E> = None;
+        for attempt in 1..=self.attempts {
+            if attempt > 1 && !self.withdraw() {
+                break;
+            }
+            let context = self.next_context(last_error.as_ref());
+            telemetry::attempt_started(None, attempt);
+            match self.retry_in_context(&context, operation, classify) {
+                Ok(value) => {
+                    telemetry::attempt_succeeded(None, attempt);
+                    self.refund(attempt);
+                    return Ok(value);
+                }
+                Err((error, delay)) => {
+                    telemetry::attempt_failed(None, attempt, delay, &error);
+                    self.wait_strategy.wait(&delay);
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    #[cfg(feature = "async")]
+    async fn run_async<F, C, O, E, R>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> R,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error,
+        R: Future<Output = Result<O, E>>,
+    {
+        let span = telemetry::backoff_span(self.attempts);
+        let started_at = Instant::now();
+        telemetry::call_started(None);
+        let result = telemetry::instrument(span, self.run_attempts_async(operation, classify)).await;
+        telemetry::call_finished(None, started_at.elapsed());
+        result
+    }
+
+    #[cfg(feature = "async")]
+    async fn run_attempts_async<F, C, O, E, R>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> R,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error,
+        R: Future<Output = Result<O, E>>,
+    {
+        if let Some(retry_budget) = &self.retry_budget {
+            retry_budget.deposit();
+        }
+        let mut last_error: Option<E> = None;
+        for attempt in 1..=self.attempts {
+            if attempt > 1 && !self.withdraw() {
+                break;
+            }
+            let context = self.next_context(last_error.as_ref());
+            telemetry::attempt_started(None, attempt);
+            match self.retry_in_context_async(&context, operation, classify).await {
+                Ok(value) => {
+                    telemetry::attempt_succeeded(None, attempt);
+                    self.refund(attempt);
+                    return Ok(value);
+                }
+                Err((error, delay)) => {
+                    telemetry::attempt_failed(None, attempt, delay, &error);
+                    self.wait_strategy.wait_async(&delay).await;
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    #[cfg(feature = "reqwest-middleware")]
+    pub(crate) fn max_time(&self) -> Option<Duration> {
+        self.max_time
+    }
+
+    pub(crate) fn ensure_synchronous(&self) -> Result<(), Error> {
+        match self.wait_strategy {
+            #[cfg(feature = "async")]
+            WaitStrategy::Asynchronous => Err(Error::new(
+                ErrorKind::Configuration,
+                "Backoff built 'as_asynchronous' cannot wait in a synchronous context".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn ensure_asynchronous(&self) -> Result<(), Error> {
+        match self.wait_strategy {
+            WaitStrategy::Asynchronous => Ok(()),
+            _ => Err(Error::new(
+                ErrorKind::Configuration,
+                "Backoff must be built 'as_asynchronous' to wait in an asynchronous context".to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn withdraw(&self) -> bool {
+        match &self.retry_budget {
+            Some(retry_budget) => retry_budget.try_withdraw(),
+            None => true,
+        }
+    }
+
+    #[cfg(feature = "reqwest-middleware")]
+    pub(crate) fn withdraw_timeout(&self) -> bool {
+        match &self.retry_budget {
+            Some(retry_budget) => retry_budget.try_withdraw_timeout(),
+            None => true,
+        }
+    }
+
+    pub(crate) fn refund(&self, attempt: usize) {
+        if let Some(retry_budget) = &self.retry_budget {
+            retry_budget.refund(attempt > 1);
+        }
+    }
+
+    pub(crate) fn retry_in_context<F, C, O, E>(
+        &mut self,
+        context: &Context<E>,
+        operation: &mut F,
+        classify: &mut C,
+    ) -> Result<O, (E, Duration)>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+    {
+        classify(operation(context)).map_err(|error| (error, self.advance(Duration::ZERO)))
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn retry_in_context_async<F, C, O, E, R>(
+        &mut self,
+        context: &Context<'_, E>,
+        operation: &mut F,
+        classify: &mut C,
+    ) -> Result<O, (E, Duration)>
+    where
+        F: FnMut(&Context<E>) -> R,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        R: Future<Output = Result<O, E>>,
+    {
+        classify(operation(context).await).map_err(|error| (error, self.advance(Duration::ZERO)))
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn retry_next_async<O, E>(&mut self, next: &mut NextAsync<'_, O, E>) -> Result<O, E> {
+        let span = telemetry::backoff_span(self.attempts);
+        let started_at = Instant::now();
+        telemetry::call_started(None);
+        let result = telemetry::instrument(span, self.run_next_async(next)).await;
+        telemetry::call_finished(None, started_at.elapsed());
+        result
+    }
+
+    #[cfg(feature = "async")]
+    async fn run_next_async<O, E>(&mut self, next: &mut NextAsync<'_, O, E>) -> Result<O, E> {
+        if let Some(retry_budget) = &self.retry_budget {
+            retry_budget.deposit();
+        }
+        let mut last_error: Option<E> = None;
+        for attempt in 1..=self.attempts {
+            if attempt > 1 && !self.withdraw() {
+                break;
+            }
+            self.next_context::<E>(None);
+            telemetry::attempt_started(None, attempt);
+            match next.run().await {
+                Ok(value) => {
+                    telemetry::attempt_succeeded(None, attempt);
+                    self.refund(attempt);
+                    return Ok(value);
+                }
+                Err(error) => {
+                    let delay = self.advance(Duration::ZERO);
+                    telemetry::attempt_classified_failed(None, attempt, delay);
+                    self.wait_strategy.wait_async(&delay).await;
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    pub(crate) fn wait_after_failure(&mut self) -> Duration {
+        let duration = self.advance(Duration::ZERO);
+        self.wait_strategy.wait(&duration);
+        duration
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn wait_after_failure_async(&mut self) -> Duration {
+        let duration = self.advance(Duration::ZERO);
+        self.wait_strategy.wait_async(&duration).await;
+        duration
+    }
+
+    pub(crate) fn advance(&mut self, minimum: Duration) -> Duration {
+        let duration = self.next_delay().max(minimum);
+        Self::compute_next(&mut self.time_strategy, self.max_time);
+        duration
+    }
+
+    fn next_delay(&self) -> Duration {
+        let duration = *Self::get_next(&self.time_strategy);
+        let duration = match self.max_time {
+            Some(max_time) => duration.min(max_time),
+            None => duration,
         };
-        let duration = Self::get_next(&self.time_strategy);
-        self.asynchronous_wait(duration).await;
-        Self::compute_next(&mut self.time_strategy);
-        Err(error)
+        self.jitter.apply(duration)
+    }
+
+    fn classify_poll<P, O, E>(result: Result<O, PollError<O, E>>, predicate: &mut P) -> Result<O, PollError<O, E>>
+    where
+        P: FnMut(&O) -> bool,
+    {
+        match result {
+            Ok(value) if !predicate(&value) => Err(PollError::Exhausted(value)),
+            result => result,
+        }
+    }
+
+    fn next_context<'a, E>(&mut self, previous_error: Option<&'a E>) -> Context<'a, E> {
+        let started_at = *self.started_at.get_or_insert_with(Instant::now);
+        self.attempt += 1;
+        Context::new(self.attempt, started_at.elapsed(), previous_error, None)
     }
 
     pub(crate) fn get_next(time_strategy: &TimeStrategy) -> &Duration {
@@ -182,45 +615,62 @@ impl Backoff {
         }
     }
 
-    fn compute_next(time_strategy: &mut TimeStrategy) {
+    fn compute_next(time_strategy: &mut TimeStrategy, max_time: Option<Duration>) {
         match time_strategy {
             TimeStrategy::Exponential {
                 initial_duration: _initial_duration,
                 ref mut duration,
                 factor
             } => {
-                *duration = duration.mul_f32(*factor);
+                *duration = match max_time {
+                    Some(max_time) if duration.as_secs_f32() * *factor >= max_time.as_secs_f32() => max_time,
+                    _ => duration.mul_f32(*factor),
+                };
             }
             _ => {}
         };
     }
+}
 
-    fn synchronous_wait(&self, duration: &Duration) {
-        match self.wait_strategy {
-            WaitStrategy::Synchronous => {
-                self.wait_strategy.synchronous_wait(duration);
-            }
-            #[cfg(feature = "async")]
-            WaitStrategy::Asynchronous => {
-                panic!("Asynchronous wait not supported in synchronous context");
-            }
-            WaitStrategy::SpinLoop => {
-                self.wait_strategy.spin_loop_wait(duration);
-            }
-        }
+impl FromStr for Backoff {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        dsl::parse(value)?.build()
     }
+}
 
-    #[cfg(feature = "async")]
-    async fn asynchronous_wait(&self, duration: &Duration) {
-        match &self.wait_strategy {
-            WaitStrategy::Asynchronous => {
-                let duration = duration.clone();
-                let strategy = self.wait_strategy.clone();
-                strategy.asynchronous_wait(&duration).await
+impl Display for Backoff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.time_strategy {
+            TimeStrategy::Constant { duration } => {
+                write!(f, "const({}", duration::format(duration))?;
             }
-            WaitStrategy::Synchronous | WaitStrategy::SpinLoop => {
-                panic!("Synchronous wait not supported in asynchronous context");
+            TimeStrategy::Exponential { initial_duration, factor, .. } => {
+                write!(f, "exp({}, x{}", duration::format(initial_duration), factor)?;
             }
         }
+        if let Some(max_time) = &self.max_time {
+            write!(f, ", max={}", duration::format(max_time))?;
+        }
+        match self.jitter {
+            Jitter::None => {}
+            Jitter::Full => write!(f, ", jitter=full")?,
+            Jitter::Equal => write!(f, ", jitter=equal")?,
+            Jitter::Proportional(ratio) => write!(f, ", jitter={}", ratio)?,
+        }
+        if self.attempts != 1 {
+            write!(f, ", attempts={}", self.attempts)?;
+        }
+        if let Some(retry_budget) = &self.retry_budget {
+            write!(f, ", budget={}", retry_budget)?;
+        }
+        match self.wait_strategy {
+            WaitStrategy::Synchronous => {}
+            #[cfg(feature = "async")]
+            WaitStrategy::Asynchronous => write!(f, ", wait=async")?,
+            WaitStrategy::SpinLoop => write!(f, ", wait=spin")?,
+        }
+        write!(f, ")")
     }
-}
\ No newline at end of file
+}
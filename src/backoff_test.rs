@@ -1,8 +1,13 @@
 use crate::backoff::{Backoff, BackoffBuilder};
-use crate::error::Error;
-use crate::fixtures::{failed_operation, failed_operation_async, success_operation, success_operation_async, TIME};
+use crate::context::Context;
+use crate::error::{Error, PollError};
+use crate::fixtures::{failed_operation, failed_operation_then_recovered_at_first_attempt, polled_operation_ready_at_third_attempt, success_operation, TIME};
+#[cfg(feature = "async")]
+use crate::fixtures::{failed_operation_async, success_operation_async};
 use rstest::rstest;
+#[cfg(feature = "async")]
 use std::future::Future;
+#[cfg(feature = "async")]
 use std::pin::Pin;
 
 #[rstest]
@@ -21,6 +26,35 @@ fn missing_with_as_method_in_builder() {
     assert_eq!("Wait strategy is required. Call one of 'as_*' method", backoff.unwrap_err().description);
 }
 
+#[rstest]
+fn zero_attempts_in_builder() {
+    let backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .with_attempts(0)
+        .as_synchronous()
+        .build();
+    assert_eq!("Attempts must be greater than zero", backoff.unwrap_err().description);
+}
+
+#[rstest]
+fn with_attempts(
+    mut failed_operation_then_recovered_at_first_attempt: impl FnMut() -> Result<(), Error>
+) {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .with_attempts(3)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let mut attempts = Vec::new();
+    let result = backoff.retry_with_context(&mut |context: &Context<Error>| {
+        attempts.push((context.attempt(), context.previous_error().is_some()));
+        failed_operation_then_recovered_at_first_attempt()
+    });
+    assert!(result.is_ok());
+    assert_eq!(vec![(1, false), (2, true), (3, true)], attempts);
+}
+
 #[rstest]
 fn with_constant_time(
     mut failed_operation: impl FnMut() -> Result<(), Error>
@@ -105,6 +139,7 @@ fn failed_with_constant_time_and_as_sync(mut failed_operation: impl FnMut() -> R
     assert_eq!(true, result.is_err());
 }
 
+#[cfg(feature = "async")]
 #[rstest]
 #[tokio::test]
 async fn success_with_constant_time_and_as_async(
@@ -120,6 +155,7 @@ async fn success_with_constant_time_and_as_async(
 }
 
 
+#[cfg(feature = "async")]
 #[rstest]
 #[tokio::test]
 async fn failed_with_constant_time_and_as_async(
@@ -154,4 +190,100 @@ fn failed_with_constant_time_and_as_spin_loop(mut failed_operation: impl FnMut()
         .unwrap();
     let result = backoff.retry(&mut failed_operation);
     assert_eq!(true, result.is_err());
-}
\ No newline at end of file
+}
+
+#[rstest]
+fn with_context(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let mut attempts = Vec::new();
+    let mut operation = |context: &Context<Error>| {
+        attempts.push((context.attempt(), context.elapsed(), context.state().is_none()));
+        failed_operation()
+    };
+    let _ = backoff.retry_with_context(&mut operation);
+    let _ = backoff.retry_with_context(&mut operation);
+    backoff.reset();
+    let _ = backoff.retry_with_context(&mut operation);
+    assert_eq!(vec![1, 2, 1], attempts.iter().map(|(attempt, _, _)| *attempt).collect::<Vec<_>>());
+    assert!(attempts[1].1 >= TIME);
+    assert!(attempts.iter().all(|(_, _, no_state)| *no_state));
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn with_context_and_as_async(
+    mut failed_operation_async: impl FnMut() -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static>>,
+) {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .with_attempts(2)
+        .as_asynchronous()
+        .build()
+        .unwrap();
+    let mut attempts = Vec::new();
+    let mut operation = |context: &Context<Error>| {
+        attempts.push((context.attempt(), context.previous_error().is_some()));
+        failed_operation_async()
+    };
+    let _ = backoff.retry_with_context_async(&mut operation).await;
+    assert_eq!(vec![(1, false), (2, true)], attempts);
+}
+
+#[rstest]
+fn poll_until_ready(
+    mut polled_operation_ready_at_third_attempt: impl FnMut() -> Result<&'static str, Error>
+) {
+    let mut backoff = BackoffBuilder::new()
+        .with_exponential_time(TIME, 2.0)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let mut is_ready = |status: &&str| *status == "READY";
+    let result = backoff.poll_until(&mut polled_operation_ready_at_third_attempt, &mut is_ready);
+    assert!(matches!(result, Err(PollError::Exhausted("PENDING"))));
+    assert!(((TIME * 2).as_millis() - (Backoff::get_next(&backoff.time_strategy).as_millis())) < 1);
+    let result = backoff.poll_until(&mut polled_operation_ready_at_third_attempt, &mut is_ready);
+    assert!(result.is_err());
+    let result = backoff.poll_until(&mut polled_operation_ready_at_third_attempt, &mut is_ready);
+    assert_eq!("READY", result.unwrap());
+    assert!(((TIME * 4).as_millis() - (Backoff::get_next(&backoff.time_strategy).as_millis())) < 1);
+}
+
+#[rstest]
+fn poll_until_with_failed_operation(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let result = backoff.poll_until(&mut failed_operation, &mut |_| true);
+    match result {
+        Err(PollError::Failed(error)) => assert_eq!("Something went wrong.", error.description),
+        _ => panic!("Expected the operation error"),
+    }
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn poll_until_ready_and_as_async() {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .as_asynchronous()
+        .build()
+        .unwrap();
+    let mut polls = 0;
+    let mut operation = || {
+        polls += 1;
+        let status = if polls == 2 { "READY" } else { "PENDING" };
+        async move { Ok::<_, Error>(status) }
+    };
+    let mut is_ready = |status: &&str| *status == "READY";
+    assert!(matches!(backoff.poll_until_async(&mut operation, &mut is_ready).await, Err(PollError::Exhausted("PENDING"))));
+    assert_eq!("READY", backoff.poll_until_async(&mut operation, &mut is_ready).await.unwrap());
+}
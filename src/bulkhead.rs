@@ -0,0 +1,257 @@
+use crate::error::{Error, ErrorKind};
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct Counters {
+    active: usize,
+    queued: usize,
+}
+
+#[derive(Debug)]
+struct Inner {
+    max_concurrent_calls: usize,
+    max_queue_size: usize,
+    queue_timeout: Option<Duration>,
+    counters: Mutex<Counters>,
+    condvar: Condvar,
+    #[cfg(feature = "async")]
+    notify: tokio::sync::Notify,
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkheadBuilder {
+    max_concurrent_calls: Option<usize>,
+    max_queue_size: usize,
+    queue_timeout: Option<Duration>,
+}
+
+impl Default for BulkheadBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BulkheadBuilder {
+    pub fn new() -> Self {
+        Self {
+            max_concurrent_calls: None,
+            max_queue_size: 0,
+            queue_timeout: None,
+        }
+    }
+
+    pub fn with_max_concurrent_calls(&mut self, calls: usize) -> &mut Self {
+        self.max_concurrent_calls = Some(calls);
+        self
+    }
+
+    pub fn with_max_queue_size(&mut self, size: usize) -> &mut Self {
+        self.max_queue_size = size;
+        self
+    }
+
+    pub fn with_queue_timeout(&mut self, duration: Duration) -> &mut Self {
+        self.queue_timeout = Some(duration);
+        self
+    }
+
+    pub fn build(&self) -> Result<Bulkhead, Error> {
+        let max_concurrent_calls = match self.max_concurrent_calls {
+            Some(calls) if calls > 0 => calls,
+            Some(_) => {
+                return Err(Error::new(ErrorKind::Configuration, "Max concurrent calls must be greater than zero".to_string()))
+            }
+            None => {
+                return Err(Error::new(ErrorKind::Configuration, "Max concurrent calls is required".to_string()))
+            }
+        };
+        Ok(Bulkhead {
+            inner: Arc::new(Inner {
+                max_concurrent_calls,
+                max_queue_size: self.max_queue_size,
+                queue_timeout: self.queue_timeout,
+                counters: Mutex::new(Counters::default()),
+                condvar: Condvar::new(),
+                #[cfg(feature = "async")]
+                notify: tokio::sync::Notify::new(),
+            }),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bulkhead {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+pub struct Permit {
+    inner: Arc<Inner>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut counters = self.inner.counters.lock().unwrap();
+        counters.active -= 1;
+        drop(counters);
+        self.inner.condvar.notify_one();
+        #[cfg(feature = "async")]
+        self.inner.notify.notify_one();
+    }
+}
+
+#[cfg(feature = "async")]
+struct QueueSlot<'a> {
+    inner: &'a Inner,
+    queued: bool,
+}
+
+#[cfg(feature = "async")]
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        if self.queued {
+            self.inner.counters.lock().unwrap().queued -= 1;
+        }
+    }
+}
+
+impl Bulkhead {
+    pub fn active_calls(&self) -> usize {
+        self.inner.counters.lock().unwrap().active
+    }
+
+    pub fn queued_calls(&self) -> usize {
+        self.inner.counters.lock().unwrap().queued
+    }
+
+    pub(crate) fn isolated(&self) -> Bulkhead {
+        Bulkhead {
+            inner: Arc::new(Inner {
+                max_concurrent_calls: self.inner.max_concurrent_calls,
+                max_queue_size: self.inner.max_queue_size,
+                queue_timeout: self.inner.queue_timeout,
+                counters: Mutex::new(Counters::default()),
+                condvar: Condvar::new(),
+                #[cfg(feature = "async")]
+                notify: tokio::sync::Notify::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn has_same_limits(&self, other: &Bulkhead) -> bool {
+        self.inner.max_concurrent_calls == other.inner.max_concurrent_calls
+            && self.inner.max_queue_size == other.inner.max_queue_size
+            && self.inner.queue_timeout == other.inner.queue_timeout
+    }
+
+    pub fn try_acquire(&self) -> Result<Permit, Error> {
+        let mut counters = self.inner.counters.lock().unwrap();
+        if counters.active < self.inner.max_concurrent_calls {
+            counters.active += 1;
+            return Ok(self.permit());
+        }
+        Err(Self::full())
+    }
+
+    pub fn acquire(&self) -> Result<Permit, Error> {
+        let mut counters = self.inner.counters.lock().unwrap();
+        if counters.active < self.inner.max_concurrent_calls {
+            counters.active += 1;
+            return Ok(self.permit());
+        }
+        if counters.queued >= self.inner.max_queue_size {
+            return Err(Self::full());
+        }
+        counters.queued += 1;
+        let deadline = self.inner.queue_timeout.map(|timeout| Instant::now() + timeout);
+        while counters.active >= self.inner.max_concurrent_calls {
+            counters = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        counters.queued -= 1;
+                        return Err(Self::full());
+                    }
+                    self.inner.condvar.wait_timeout(counters, deadline - now).unwrap().0
+                }
+                None => self.inner.condvar.wait(counters).unwrap(),
+            };
+        }
+        counters.queued -= 1;
+        counters.active += 1;
+        Ok(self.permit())
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn acquire_async(&self) -> Result<Permit, Error> {
+        let mut slot = {
+            let mut counters = self.inner.counters.lock().unwrap();
+            if counters.active < self.inner.max_concurrent_calls {
+                counters.active += 1;
+                return Ok(self.permit());
+            }
+            if counters.queued >= self.inner.max_queue_size {
+                return Err(Self::full());
+            }
+            counters.queued += 1;
+            QueueSlot {
+                inner: &self.inner,
+                queued: true,
+            }
+        };
+        let deadline = self.inner.queue_timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        loop {
+            let notified = self.inner.notify.notified();
+            {
+                let mut counters = self.inner.counters.lock().unwrap();
+                if counters.active < self.inner.max_concurrent_calls {
+                    counters.queued -= 1;
+                    counters.active += 1;
+                    slot.queued = false;
+                    return Ok(self.permit());
+                }
+            }
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                        return Err(Self::full());
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    pub fn execute<F, O, E>(&self, operation: F) -> Result<O, E>
+    where
+        F: FnOnce() -> Result<O, E>,
+        E: From<Error>,
+    {
+        let _permit = self.acquire()?;
+        operation()
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn execute_async<F, O, E, R>(&self, operation: F) -> Result<O, E>
+    where
+        F: FnOnce() -> R,
+        E: From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        let _permit = self.acquire_async().await?;
+        operation().await
+    }
+
+    fn permit(&self) -> Permit {
+        Permit {
+            inner: self.inner.clone(),
+        }
+    }
+
+    fn full() -> Error {
+        Error::new(ErrorKind::BulkheadFull, "Bulkhead is full".to_string())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::backoff::BackoffBuilder;
+use crate::bulkhead::BulkheadBuilder;
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{success_operation, TIME};
+use rstest::rstest;
+use std::time::Instant;
+
+#[rstest]
+fn missing_max_concurrent_calls_in_builder() {
+    let bulkhead = BulkheadBuilder::new()
+        .build();
+    assert_eq!("Max concurrent calls is required", bulkhead.unwrap_err().description);
+}
+
+#[rstest]
+fn zero_max_concurrent_calls_in_builder() {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(0)
+        .build();
+    assert_eq!(ErrorKind::Configuration, bulkhead.unwrap_err().kind());
+}
+
+#[rstest]
+fn rejects_when_full_without_queue() {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(1)
+        .build()
+        .unwrap();
+    let permit = bulkhead.acquire().unwrap();
+    assert_eq!(1, bulkhead.active_calls());
+    assert_eq!(ErrorKind::BulkheadFull, bulkhead.acquire().unwrap_err().kind());
+    drop(permit);
+    assert_eq!(0, bulkhead.active_calls());
+    assert!(bulkhead.try_acquire().is_ok());
+}
+
+#[rstest]
+fn rejects_after_queue_timeout() {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(1)
+        .with_max_queue_size(1)
+        .with_queue_timeout(TIME)
+        .build()
+        .unwrap();
+    let _permit = bulkhead.acquire().unwrap();
+    let started_at = Instant::now();
+    assert_eq!(ErrorKind::BulkheadFull, bulkhead.acquire().unwrap_err().kind());
+    assert!(started_at.elapsed() >= TIME);
+    assert_eq!(0, bulkhead.queued_calls());
+}
+
+#[rstest]
+fn queued_call_acquires_released_permit() {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(1)
+        .with_max_queue_size(1)
+        .build()
+        .unwrap();
+    let permit = bulkhead.acquire().unwrap();
+    let queued = bulkhead.clone();
+    let handle = std::thread::spawn(move || queued.execute(|| Ok::<_, Error>(queued.active_calls())));
+    std::thread::sleep(TIME);
+    assert_eq!(1, bulkhead.queued_calls());
+    assert_eq!(ErrorKind::BulkheadFull, bulkhead.acquire().unwrap_err().kind());
+    drop(permit);
+    assert_eq!(1, handle.join().unwrap().unwrap());
+    assert_eq!(0, bulkhead.active_calls());
+}
+
+#[rstest]
+fn with_circuit_breaker(mut success_operation: impl FnMut() -> Result<(), Error>) {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(1)
+        .build()
+        .unwrap();
+    let mut circuit_breaker = CircuitBreakerBuilder::new()
+        .with_reset_timeout(TIME)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .with_bulkhead(bulkhead.clone())
+        .build()
+        .unwrap();
+    assert!(circuit_breaker.retry(&mut success_operation).is_ok());
+    let _permit = bulkhead.acquire().unwrap();
+    let error = circuit_breaker.retry(&mut success_operation).unwrap_err();
+    assert_eq!(ErrorKind::BulkheadFull, error.kind());
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn queued_call_acquires_released_permit_as_async() {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(1)
+        .with_max_queue_size(1)
+        .with_queue_timeout(TIME * 10)
+        .build()
+        .unwrap();
+    let permit = bulkhead.acquire_async().await.unwrap();
+    let queued = bulkhead.clone();
+    let handle = tokio::spawn(async move {
+        queued.execute_async(|| async { Ok::<_, Error>(()) }).await
+    });
+    tokio::time::sleep(TIME).await;
+    assert_eq!(1, bulkhead.queued_calls());
+    drop(permit);
+    assert!(handle.await.unwrap().is_ok());
+    assert_eq!(0, bulkhead.active_calls());
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn cancelled_queued_call_releases_queue_slot() {
+    let bulkhead = BulkheadBuilder::new()
+        .with_max_concurrent_calls(1)
+        .with_max_queue_size(1)
+        .build()
+        .unwrap();
+    let permit = bulkhead.acquire_async().await.unwrap();
+    assert!(tokio::time::timeout(TIME, bulkhead.acquire_async()).await.is_err());
+    assert_eq!(0, bulkhead.queued_calls());
+    let queued = bulkhead.clone();
+    let handle = tokio::spawn(async move { queued.acquire_async().await.map(|_| ()) });
+    tokio::time::sleep(TIME).await;
+    assert_eq!(1, bulkhead.queued_calls());
+    drop(permit);
+    assert!(handle.await.unwrap().is_ok());
+    assert_eq!(0, bulkhead.queued_calls());
+}
@@ -1,42 +1,84 @@
-use crate::backoff::{Backoff, WaitStrategy};
-use crate::error::Error;
+use crate::backoff::{Backoff, BackoffBuilder, WaitStrategy};
+use crate::bulkhead::{Bulkhead, Permit};
+use crate::context::Context;
+use crate::error::{Error, ErrorKind, PollError};
+use crate::fallback::{Outcome, Reason};
+use crate::history::{History, Record};
+use crate::retry_budget::RetryBudget;
+use crate::snapshot::CircuitBreakerSnapshot;
+use crate::state_store::{Change, StateStore, Synchronization};
+use crate::telemetry;
 use std::fmt::{Display, Formatter};
+#[cfg(feature = "async")]
 use std::future::Future;
-use std::time::Duration;
+use std::sync::Arc;
+#[cfg(any(feature = "tower", feature = "reqwest-middleware", all(feature = "macros", feature = "async")))]
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum State {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum State {
     Closed,
     Open,
     HalfOpen,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Override {
+    ForcedOpen,
+    ForcedClosed,
+    Disabled,
+}
+
 #[derive(Debug, Clone)]
 pub struct CircuitBreakerBuilder {
+    name: Option<String>,
     attempts: Option<usize>,
     failure_threshold: Option<usize>,
     reset_timeout: Option<Duration>,
     backoff: Option<Backoff>,
+    backoff_builder: Option<BackoffBuilder>,
+    bulkhead: Option<Bulkhead>,
+    retry_budget: Option<RetryBudget>,
+    state_store: Option<Arc<dyn StateStore>>,
+}
+
+impl Default for CircuitBreakerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CircuitBreakerBuilder {
     pub fn new() -> Self {
         Self {
+            name: None,
             attempts: None,
             failure_threshold: None,
             reset_timeout: None,
             backoff: None,
+            backoff_builder: None,
+            bulkhead: None,
+            retry_budget: None,
+            state_store: None,
         }
     }
 
+    pub fn with_name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn with_attempts(&mut self, attempts: usize) -> &mut Self {
-        assert!(attempts > 0);
         self.attempts = Some(attempts);
         self
     }
 
     pub fn with_failure_threshold(&mut self, threshold: usize) -> &mut Self {
-        assert!(threshold > 1);
         self.failure_threshold = Some(threshold);
         self
     }
@@ -48,35 +90,96 @@ impl CircuitBreakerBuilder {
 
     pub fn with_backoff(&mut self, backoff: Backoff) -> &mut Self {
         self.backoff = Some(backoff);
+        self.backoff_builder = None;
+        self
+    }
+
+    pub fn with_backoff_builder(&mut self, backoff: BackoffBuilder) -> &mut Self {
+        self.backoff_builder = Some(backoff);
+        self.backoff = None;
+        self
+    }
+
+    pub fn with_bulkhead(&mut self, bulkhead: Bulkhead) -> &mut Self {
+        self.bulkhead = Some(bulkhead);
+        self
+    }
+
+    pub fn with_retry_budget(&mut self, retry_budget: RetryBudget) -> &mut Self {
+        self.retry_budget = Some(retry_budget);
+        self
+    }
+
+    pub fn with_state_store(&mut self, state_store: Arc<dyn StateStore>) -> &mut Self {
+        self.state_store = Some(state_store);
         self
     }
 
+    pub(crate) fn has_state_store(&self) -> bool {
+        self.state_store.is_some()
+    }
+
+    pub(crate) fn for_key(&self, key: &str) -> CircuitBreakerBuilder {
+        let mut builder = self.clone();
+        builder.name = Some(match &self.name {
+            Some(name) => format!("{}/{}", name, key),
+            None => key.to_string(),
+        });
+        builder.bulkhead = self.bulkhead.as_ref().map(Bulkhead::isolated);
+        builder.retry_budget = self.retry_budget.as_ref().map(RetryBudget::isolated);
+        builder.backoff_builder = self.backoff_builder.as_ref().map(BackoffBuilder::isolated);
+        if let Some(backoff) = &mut builder.backoff {
+            backoff.retry_budget = backoff.retry_budget.as_ref().map(RetryBudget::isolated);
+        }
+        builder
+    }
+
+    pub fn restore(&self, snapshot: &CircuitBreakerSnapshot) -> Result<CircuitBreaker, Error> {
+        let mut circuit_breaker = self.build()?;
+        circuit_breaker.restore(snapshot);
+        Ok(circuit_breaker)
+    }
+
     pub fn build(&self) -> Result<CircuitBreaker, Error> {
         if self.reset_timeout.is_none() {
-            return Err(Error {
-                description: "Reset timeout is required".to_string(),
-            })
+            return Err(Error::new(ErrorKind::Configuration, "Reset timeout is required".to_string()))
         }
-        if self.backoff.is_none() {
-            return Err(Error {
-                description: "Backoff is required".to_string(),
-            })
+        let backoff = match (&self.backoff, &self.backoff_builder) {
+            (Some(backoff), _) => backoff.clone(),
+            (None, Some(backoff)) => backoff.build()?,
+            (None, None) => return Err(Error::new(ErrorKind::Configuration, "Backoff is required".to_string())),
+        };
+        if self.attempts == Some(0) {
+            return Err(Error::new(ErrorKind::Configuration, "Attempts must be greater than zero".to_string()))
         }
-        Ok(CircuitBreaker {
+        if self.failure_threshold.is_some_and(|threshold| threshold < 2) {
+            return Err(Error::new(ErrorKind::Configuration, "Failure threshold must be greater than one".to_string()))
+        }
+        let circuit_breaker = CircuitBreaker {
+            name: self.name.clone(),
             failed_attempts: 0,
             attempts: self.attempts.unwrap_or(1),
             failure_count: 0,
             failure_threshold: self.failure_threshold.unwrap_or(2),
             reset_timeout: self.reset_timeout.unwrap().clone(),
             wait_strategy: WaitStrategy::Synchronous,
-            backoff: self.backoff.clone().unwrap(),
+            backoff,
+            bulkhead: self.bulkhead.clone(),
+            retry_budget: self.retry_budget.clone(),
+            state_store: self.state_store.clone(),
+            pending: Vec::new(),
             state: State::Closed,
-        })
+            opened_at: None,
+            manual_override: None,
+        };
+        telemetry::state_initialized(circuit_breaker.name(), &circuit_breaker.state);
+        Ok(circuit_breaker)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct CircuitBreaker {
+    name: Option<String>,
     pub(crate) failed_attempts: usize,
     attempts: usize,
     pub(crate) failure_count: usize,
@@ -84,14 +187,271 @@ pub struct CircuitBreaker {
     reset_timeout: Duration,
     wait_strategy: WaitStrategy,
     backoff: Backoff,
+    bulkhead: Option<Bulkhead>,
+    retry_budget: Option<RetryBudget>,
+    state_store: Option<Arc<dyn StateStore>>,
+    pending: Vec<Change>,
     pub(crate) state: State,
+    opened_at: Option<Instant>,
+    manual_override: Option<Override>,
+}
+
+enum Failure<E> {
+    Rejected(Error),
+    Exhausted(History<E>),
+}
+
+impl<E: From<Error>> Failure<E> {
+    fn into_history(self, state: &State) -> History<E> {
+        match self {
+            Failure::Rejected(error) => {
+                let mut history = History::new();
+                history.push(Record::new(0, Duration::ZERO, state.clone(), error.into()));
+                history
+            }
+            Failure::Exhausted(history) => history,
+        }
+    }
+
+    fn into_last_error(self) -> E {
+        match self {
+            Failure::Rejected(error) => error.into(),
+            Failure::Exhausted(history) => history.into_last_error().unwrap_or_else(|| {
+                Error::new(ErrorKind::Operation, "No attempt recorded".to_string()).into()
+            }),
+        }
+    }
 }
 
 impl CircuitBreaker {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn manual_override(&self) -> Option<&Override> {
+        self.manual_override.as_ref()
+    }
+
+    pub fn force_open(&mut self) {
+        self.apply_override(Some(Override::ForcedOpen));
+    }
+
+    pub fn force_closed(&mut self) {
+        self.apply_override(Some(Override::ForcedClosed));
+    }
+
+    pub fn disable(&mut self) {
+        self.apply_override(Some(Override::Disabled));
+    }
+
+    pub fn clear_override(&mut self) {
+        self.apply_override(None);
+    }
+
+    fn apply_override(&mut self, manual_override: Option<Override>) {
+        if self.change_override(manual_override.clone()) {
+            self.record(Change::Override(manual_override));
+            self.synchronize();
+        }
+    }
+
+    fn change_override(&mut self, manual_override: Option<Override>) -> bool {
+        if self.manual_override == manual_override {
+            return false;
+        }
+        telemetry::override_changed(self.name(), self.manual_override.as_ref(), manual_override.as_ref());
+        self.manual_override = manual_override;
+        true
+    }
+
     pub fn reset(&mut self) {
-        self.state = State::Closed;
+        self.restart();
+        self.synchronize();
+    }
+
+    fn restart(&mut self) {
+        self.change_state(State::Closed);
+        self.opened_at = None;
         self.failure_count = 0;
         self.backoff.reset();
+        self.record(Change::Reset);
+    }
+
+    pub fn reconfigure(&mut self, builder: &CircuitBreakerBuilder) -> Result<(), Error> {
+        let configured = builder.build()?;
+        if configured.name.is_some() {
+            self.name = configured.name;
+        }
+        self.attempts = configured.attempts;
+        self.failure_threshold = configured.failure_threshold;
+        self.reset_timeout = configured.reset_timeout;
+        let mut backoff = configured.backoff;
+        backoff.retry_budget = keep_retry_budget(self.backoff.retry_budget.take(), backoff.retry_budget);
+        self.backoff = backoff;
+        self.bulkhead = match (self.bulkhead.take(), configured.bulkhead) {
+            (Some(current), Some(bulkhead)) if current.has_same_limits(&bulkhead) => Some(current),
+            (_, bulkhead) => bulkhead,
+        };
+        self.retry_budget = keep_retry_budget(self.retry_budget.take(), configured.retry_budget);
+        if configured.state_store.is_some() {
+            self.state_store = configured.state_store;
+        }
+        if self.state == State::Closed
+            && self.failure_count >= self.failure_threshold
+            && self.manual_override != Some(Override::ForcedClosed) {
+            self.open();
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> CircuitBreakerSnapshot {
+        CircuitBreakerSnapshot {
+            taken_at: SystemTime::now(),
+            state: self.state.clone(),
+            manual_override: self.manual_override.clone(),
+            failed_attempts: self.failed_attempts,
+            failure_count: self.failure_count,
+            open_for: self.opened_at.map(|opened_at| opened_at.elapsed()),
+            retry_budget: self.retry_budget()
+                .map(|retry_budget| retry_budget.snapshot())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &CircuitBreakerSnapshot) {
+        self.apply(snapshot);
+        if let Some(retry_budget) = self.retry_budget() {
+            retry_budget.restore(&snapshot.retry_budget, snapshot.elapsed());
+        }
+        self.record(Change::Restore(snapshot.clone()));
+        self.synchronize();
+    }
+
+    fn apply(&mut self, snapshot: &CircuitBreakerSnapshot) {
+        let elapsed = snapshot.elapsed();
+        self.change_state(snapshot.state.clone());
+        self.change_override(snapshot.manual_override.clone());
+        self.failed_attempts = snapshot.failed_attempts;
+        self.failure_count = snapshot.failure_count;
+        self.opened_at = snapshot.open_for
+            .and_then(|open_for| Instant::now().checked_sub(open_for + elapsed))
+            .filter(|opened_at| opened_at.elapsed() < self.reset_timeout);
+        if self.state == State::Open && self.opened_at.is_none() {
+            self.change_state(State::HalfOpen);
+        }
+    }
+
+    fn record(&mut self, change: Change) {
+        if self.state_store.is_some() {
+            self.pending.push(change);
+        }
+    }
+
+    fn synchronization(&mut self) -> Option<Synchronization> {
+        let state_store = self.state_store.clone()?;
+        Some(Synchronization {
+            state_store,
+            local: self.snapshot(),
+            changes: std::mem::take(&mut self.pending),
+        })
+    }
+
+    fn synchronized(&mut self, result: Result<Option<CircuitBreakerSnapshot>, Error>) {
+        match result {
+            Ok(Some(snapshot)) => {
+                let failed_attempts = self.failed_attempts;
+                self.apply(&snapshot);
+                self.failed_attempts = failed_attempts;
+            }
+            Ok(None) => {}
+            Err(error) => telemetry::state_store_failed(self.name(), &error),
+        }
+    }
+
+    fn synchronize(&mut self) {
+        if let Some(synchronization) = self.synchronization() {
+            let result = synchronization.run();
+            self.synchronized(result);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn synchronize_async(&mut self) {
+        if let Some(synchronization) = self.synchronization() {
+            let result = synchronization.run_async().await;
+            self.synchronized(result);
+        }
+    }
+
+    #[cfg(any(feature = "tower", feature = "reqwest-middleware", all(feature = "macros", feature = "async")))]
+    pub(crate) async fn synchronize_shared(circuit_breaker: &Mutex<CircuitBreaker>) {
+        let synchronization = circuit_breaker.lock().unwrap().synchronization();
+        if let Some(synchronization) = synchronization {
+            let result = synchronization.run_async().await;
+            circuit_breaker.lock().unwrap().synchronized(result);
+        }
+    }
+
+    fn count_failure(&mut self) {
+        self.failure_count += 1;
+        self.record(Change::Failure {
+            threshold: self.failure_threshold,
+        });
+    }
+
+    pub fn record_success(&mut self) {
+        self.succeed();
+        self.synchronize();
+    }
+
+    pub fn record_failure(&mut self) {
+        self.fail();
+        self.synchronize();
+    }
+
+    pub(crate) fn succeed(&mut self) {
+        if self.manual_override == Some(Override::Disabled) {
+            return;
+        }
+        self.change_state(State::Closed);
+        self.opened_at = None;
+        self.failure_count = 0;
+        self.record(Change::Success);
+    }
+
+    pub(crate) fn fail(&mut self) {
+        if self.manual_override == Some(Override::Disabled) {
+            return;
+        }
+        self.count_failure();
+        if self.failure_count >= self.failure_threshold && self.manual_override != Some(Override::ForcedClosed) {
+            self.open();
+        }
+    }
+
+    pub(crate) fn open(&mut self) {
+        self.opened_at = Some(Instant::now());
+        self.change_state(State::Open);
+        self.record(Change::State(State::Open));
+    }
+
+    fn transition(&mut self, state: State) {
+        if self.change_state(state.clone()) {
+            self.record(Change::State(state));
+        }
+    }
+
+    fn change_state(&mut self, state: State) -> bool {
+        if self.state == state {
+            return false;
+        }
+        telemetry::state_changed(self.name(), &self.state, &state);
+        self.state = state;
+        true
     }
 
     pub fn retry<F, O, E>(&mut self, operation: &mut F) -> Result<O, E>
@@ -99,45 +459,162 @@ impl CircuitBreaker {
         F: FnMut() -> Result<O, E>,
         E: std::error::Error + From<Error>,
     {
-        if self.state == State::Open {
-            let error = Error {
-                description: "Circuit breaker is open".to_string(),
-            };
-            return Err(error.into());
+        self.retry_with_context(&mut |_: &Context<E>| operation())
+    }
+
+    pub fn retry_with_context<F, O, E>(&mut self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        E: std::error::Error + From<Error>,
+    {
+        self.run(operation, &mut |result| result)
+            .map_err(Failure::into_last_error)
+    }
+
+    pub fn poll_until<F, P, O, E>(&mut self, operation: &mut F, predicate: &mut P) -> Result<O, PollError<O, E>>
+    where
+        F: FnMut() -> Result<O, E>,
+        P: FnMut(&O) -> bool,
+        E: std::error::Error + From<Error>,
+    {
+        let mut backoff = self.backoff.clone();
+        backoff.reset();
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            let value = self.retry(operation).map_err(PollError::Failed)?;
+            if predicate(&value) {
+                return Ok(value);
+            }
+            if polls >= backoff.attempts || !backoff.withdraw() {
+                return Err(PollError::Exhausted(value));
+            }
+            backoff.wait_after_failure();
+        }
+    }
+
+    pub fn retry_with_history<F, O, E>(&mut self, operation: &mut F) -> Result<O, History<E>>
+    where
+        F: FnMut() -> Result<O, E>,
+        E: std::error::Error + From<Error>,
+    {
+        self.run(&mut |_: &Context<E>| operation(), &mut |result| result)
+            .map_err(|failure| failure.into_history(&self.state))
+    }
+
+    pub fn retry_with_fallback<F, G, O, E>(&mut self, operation: &mut F, fallback: &mut G) -> Outcome<O>
+    where
+        F: FnMut() -> Result<O, E>,
+        G: FnMut(Reason<E>) -> O,
+        E: std::error::Error + From<Error>,
+    {
+        match self.run(&mut |_: &Context<E>| operation(), &mut |result| result) {
+            Ok(value) => Outcome::Primary(value),
+            Err(Failure::Rejected(error)) => Outcome::Fallback(fallback(Reason::Rejected(error))),
+            Err(Failure::Exhausted(history)) => {
+                Outcome::Fallback(fallback(Reason::Exhausted(Failure::Exhausted(history).into_last_error())))
+            }
+        }
+    }
+
+    fn run<F, C, O, E>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, Failure<E>>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error + From<Error>,
+    {
+        let _span = telemetry::circuit_breaker_span(self.name(), &self.state).entered();
+        let started_at = Instant::now();
+        telemetry::call_started(self.name());
+        let result = self.run_attempts(operation, classify);
+        telemetry::call_finished(self.name(), started_at.elapsed());
+        result
+    }
+
+    fn run_attempts<F, C, O, E>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, Failure<E>>
+    where
+        F: FnMut(&Context<E>) -> Result<O, E>,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error + From<Error>,
+    {
+        let rejection = self.rejection();
+        if self.manual_override == Some(Override::Disabled) {
+            let context = self.context(1, Instant::now(), None);
+            return classify(operation(&context)).map_err(|error| self.pass_through_failure(error));
+        }
+        if let Some(error) = rejection {
+            return Err(self.rejected(error));
         }
+        let _permit = self.acquire().map_err(|error| self.rejected(error))?;
         self.reset();
-        self.state = State::Closed;
-        let mut last_error: Option<E> = None;
+        self.failed_attempts = 0;
+        if let Some(retry_budget) = self.retry_budget() {
+            retry_budget.deposit();
+        }
+        let started_at = Instant::now();
+        let mut attempt = 0;
+        let mut history = History::new();
         while self.failed_attempts < self.attempts {
             if self.state == State::HalfOpen {
-                match self.backoff.retry(operation) {
+                if attempt > 0 && !self.withdraw() {
+                    return Err(Failure::Exhausted(history));
+                }
+                attempt += 1;
+                let context = self.context(attempt, started_at, history.last_error());
+                telemetry::attempt_started(self.name(), attempt);
+                match self.backoff.retry_in_context(&context, operation, classify) {
                     Ok(value) => {
-                        self.state = State::Closed;
+                        telemetry::attempt_succeeded(self.name(), attempt);
+                        self.refund(attempt);
+                        self.transition(State::Closed);
+                        self.synchronize();
                         return Ok(value);
                     }
-                    Err(error) => {
-                        self.state = State::Closed;
+                    Err((error, delay)) => {
+                        telemetry::attempt_failed(self.name(), attempt, delay + self.reset_timeout, &error);
+                        self.backoff.wait_strategy.wait(&delay);
+                        history.push(Record::new(attempt, delay + self.reset_timeout, self.state.clone(), error));
+                        self.transition(State::Closed);
+                        self.synchronize();
                         self.failed_attempts += 1;
-                        self.backoff.wait_strategy.synchronous_wait(&self.reset_timeout);
-                        last_error = Some(error);
+                        self.wait_strategy.synchronous_wait(&self.reset_timeout);
                     }
                 }
             }
-            while self.failure_count <= self.failure_threshold - 1 {
-                let error = match self.backoff.retry(operation) {
+            while self.failure_count < self.failure_threshold {
+                if attempt > 0 && !self.withdraw() {
+                    return Err(Failure::Exhausted(history));
+                }
+                attempt += 1;
+                let context = self.context(attempt, started_at, history.last_error());
+                telemetry::attempt_started(self.name(), attempt);
+                let (error, delay) = match self.backoff.retry_in_context(&context, operation, classify) {
                     Ok(value) => {
-                        self.state = State::Closed;
+                        telemetry::attempt_succeeded(self.name(), attempt);
+                        self.refund(attempt);
+                        self.transition(State::Closed);
+                        self.synchronize();
                         return Ok(value)
                     },
                     Err(value) => value,
                 };
-                last_error = Some(error);
-                self.failure_count += 1;
+                telemetry::attempt_failed(self.name(), attempt, delay, &error);
+                self.backoff.wait_strategy.wait(&delay);
+                history.push(Record::new(attempt, delay, self.state.clone(), error));
+                self.count_failure();
+                self.synchronize();
+            }
+            if self.manual_override == Some(Override::ForcedClosed) {
+                break;
             }
-            self.state = State::HalfOpen;
-            self.backoff.wait_strategy.synchronous_wait(&self.reset_timeout);
+            history.add_delay_to_last(self.reset_timeout);
+            self.open();
+            self.synchronize();
+            self.wait_strategy.synchronous_wait(&self.reset_timeout);
+            self.transition(State::HalfOpen);
+            self.synchronize();
         }
-        Err(last_error.unwrap())
+        Err(Failure::Exhausted(history))
     }
 
     #[cfg(feature = "async")]
@@ -147,50 +624,278 @@ impl CircuitBreaker {
         E: std::error::Error + From<Error>,
         R: Future<Output = Result<O, E>>,
     {
-        if self.state == State::Open {
-            let error = Error {
-                description: "Circuit breaker is open".to_string(),
-            };
-            return Err(error.into());
+        self.retry_with_context_async(&mut |_: &Context<E>| operation()).await
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn retry_with_context_async<F, O, E, R>(&mut self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut(&Context<E>) -> R,
+        E: std::error::Error + From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.run_async(operation, &mut |result| result).await
+            .map_err(Failure::into_last_error)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn poll_until_async<F, P, O, E, R>(
+        &mut self,
+        operation: &mut F,
+        predicate: &mut P,
+    ) -> Result<O, PollError<O, E>>
+    where
+        F: FnMut() -> R,
+        P: FnMut(&O) -> bool,
+        E: std::error::Error + From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        let mut backoff = self.backoff.clone();
+        backoff.reset();
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            let value = self.retry_async(operation).await.map_err(PollError::Failed)?;
+            if predicate(&value) {
+                return Ok(value);
+            }
+            if polls >= backoff.attempts || !backoff.withdraw() {
+                return Err(PollError::Exhausted(value));
+            }
+            backoff.wait_after_failure_async().await;
         }
-        self.reset();
-        self.state = State::Closed;
-        let mut last_error: Option<E> = None;
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn retry_with_history_async<F, O, E, R>(&mut self, operation: &mut F) -> Result<O, History<E>>
+    where
+        F: FnMut() -> R,
+        E: std::error::Error + From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.run_async(&mut |_: &Context<E>| operation(), &mut |result| result).await
+            .map_err(|failure| failure.into_history(&self.state))
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn retry_with_fallback_async<F, G, O, E, R, RG>(&mut self, operation: &mut F, fallback: &mut G) -> Outcome<O>
+    where
+        F: FnMut() -> R,
+        G: FnMut(Reason<E>) -> RG,
+        E: std::error::Error + From<Error>,
+        R: Future<Output = Result<O, E>>,
+        RG: Future<Output = O>,
+    {
+        match self.run_async(&mut |_: &Context<E>| operation(), &mut |result| result).await {
+            Ok(value) => Outcome::Primary(value),
+            Err(Failure::Rejected(error)) => Outcome::Fallback(fallback(Reason::Rejected(error)).await),
+            Err(Failure::Exhausted(history)) => {
+                Outcome::Fallback(fallback(Reason::Exhausted(Failure::Exhausted(history).into_last_error())).await)
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn run_async<F, C, O, E, R>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, Failure<E>>
+    where
+        F: FnMut(&Context<E>) -> R,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error + From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        let span = telemetry::circuit_breaker_span(self.name(), &self.state);
+        let started_at = Instant::now();
+        telemetry::call_started(self.name());
+        let result = telemetry::instrument(span, self.run_attempts_async(operation, classify)).await;
+        telemetry::call_finished(self.name(), started_at.elapsed());
+        result
+    }
+
+    #[cfg(feature = "async")]
+    async fn run_attempts_async<F, C, O, E, R>(&mut self, operation: &mut F, classify: &mut C) -> Result<O, Failure<E>>
+    where
+        F: FnMut(&Context<E>) -> R,
+        C: FnMut(Result<O, E>) -> Result<O, E>,
+        E: std::error::Error + From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.synchronize_async().await;
+        let rejection = self.current_rejection();
+        if self.manual_override == Some(Override::Disabled) {
+            let context = self.context(1, Instant::now(), None);
+            return classify(operation(&context).await).map_err(|error| self.pass_through_failure(error));
+        }
+        if let Some(error) = rejection {
+            return Err(self.rejected(error));
+        }
+        let _permit = self.acquire_async().await.map_err(|error| self.rejected(error))?;
+        self.restart();
+        self.failed_attempts = 0;
+        self.synchronize_async().await;
+        if let Some(retry_budget) = self.retry_budget() {
+            retry_budget.deposit();
+        }
+        let started_at = Instant::now();
+        let mut attempt = 0;
+        let mut history = History::new();
         while self.failed_attempts < self.attempts {
             if self.state == State::HalfOpen {
-                match self.backoff.retry_async(operation).await {
+                if attempt > 0 && !self.withdraw() {
+                    return Err(Failure::Exhausted(history));
+                }
+                attempt += 1;
+                let context = self.context(attempt, started_at, history.last_error());
+                telemetry::attempt_started(self.name(), attempt);
+                match self.backoff.retry_in_context_async(&context, operation, classify).await {
                     Ok(value) => {
-                        self.state = State::Closed;
+                        telemetry::attempt_succeeded(self.name(), attempt);
+                        self.refund(attempt);
+                        self.transition(State::Closed);
+                        self.synchronize_async().await;
                         return Ok(value);
                     }
-                    Err(error) => {
-                        self.state = State::Closed;
+                    Err((error, delay)) => {
+                        telemetry::attempt_failed(self.name(), attempt, delay + self.reset_timeout, &error);
+                        self.backoff.wait_strategy.wait_async(&delay).await;
+                        history.push(Record::new(attempt, delay + self.reset_timeout, self.state.clone(), error));
+                        self.transition(State::Closed);
+                        self.synchronize_async().await;
                         self.failed_attempts += 1;
-                        self.backoff.wait_strategy.synchronous_wait(&self.reset_timeout);
-                        last_error = Some(error);
+                        self.wait_strategy.synchronous_wait(&self.reset_timeout);
                     }
                 }
             }
-            while self.failure_count <= self.failure_threshold - 1 {
-                let error = match self.backoff.retry_async(operation).await {
+            while self.failure_count < self.failure_threshold {
+                if attempt > 0 && !self.withdraw() {
+                    return Err(Failure::Exhausted(history));
+                }
+                attempt += 1;
+                let context = self.context(attempt, started_at, history.last_error());
+                telemetry::attempt_started(self.name(), attempt);
+                let (error, delay) = match self.backoff.retry_in_context_async(&context, operation, classify).await {
                     Ok(value) => {
-                        self.state = State::Closed;
+                        telemetry::attempt_succeeded(self.name(), attempt);
+                        self.refund(attempt);
+                        self.transition(State::Closed);
+                        self.synchronize_async().await;
                         return Ok(value)
                     },
                     Err(value) => value,
                 };
-                last_error = Some(error);
-                self.failure_count += 1;
+                telemetry::attempt_failed(self.name(), attempt, delay, &error);
+                self.backoff.wait_strategy.wait_async(&delay).await;
+                history.push(Record::new(attempt, delay, self.state.clone(), error));
+                self.count_failure();
+                self.synchronize_async().await;
+            }
+            if self.manual_override == Some(Override::ForcedClosed) {
+                break;
             }
-            self.state = State::HalfOpen;
-            self.backoff.wait_strategy.synchronous_wait(&self.reset_timeout);
+            history.add_delay_to_last(self.reset_timeout);
+            self.open();
+            self.synchronize_async().await;
+            self.wait_strategy.synchronous_wait(&self.reset_timeout);
+            self.transition(State::HalfOpen);
+            self.synchronize_async().await;
+        }
+        Err(Failure::Exhausted(history))
+    }
+
+    pub(crate) fn open_remaining(&self) -> Option<Duration> {
+        if self.state != State::Open || self.manual_override.is_some() {
+            return None;
         }
-        Err(last_error.unwrap())
+        self.opened_at
+            .and_then(|opened_at| self.reset_timeout.checked_sub(opened_at.elapsed()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    #[cfg(any(feature = "tower", feature = "reqwest-middleware"))]
+    pub(crate) fn backoff(&self) -> &Backoff {
+        &self.backoff
+    }
+
+    fn pass_through_failure<E>(&self, error: E) -> Failure<E> {
+        let mut history = History::new();
+        history.push(Record::new(1, Duration::ZERO, self.state.clone(), error));
+        Failure::Exhausted(history)
+    }
+
+    fn rejected<E>(&self, error: Error) -> Failure<E> {
+        telemetry::rejected(self.name(), &error);
+        Failure::Rejected(error)
+    }
+
+    pub(crate) fn rejection(&mut self) -> Option<Error> {
+        self.synchronize();
+        self.current_rejection()
+    }
+
+    pub(crate) fn current_rejection(&self) -> Option<Error> {
+        if self.manual_override == Some(Override::ForcedOpen) {
+            return Some(Error::new(ErrorKind::CircuitOpen, match self.name() {
+                Some(name) => format!("Circuit breaker '{}' is forced open", name),
+                None => "Circuit breaker is forced open".to_string(),
+            }));
+        }
+        if self.open_remaining().is_some() {
+            return Some(Error::new(ErrorKind::CircuitOpen, match self.name() {
+                Some(name) => format!("Circuit breaker '{}' is open", name),
+                None => "Circuit breaker is open".to_string(),
+            }));
+        }
+        None
+    }
+
+    fn retry_budget(&self) -> Option<&RetryBudget> {
+        self.retry_budget.as_ref().or(self.backoff.retry_budget.as_ref())
+    }
+
+    fn withdraw(&self) -> bool {
+        match self.retry_budget() {
+            Some(retry_budget) => retry_budget.try_withdraw(),
+            None => true,
+        }
+    }
+
+    fn refund(&self, attempt: usize) {
+        if let Some(retry_budget) = self.retry_budget() {
+            retry_budget.refund(attempt > 1);
+        }
+    }
+
+    fn acquire(&self) -> Result<Option<Permit>, Error> {
+        match &self.bulkhead {
+            Some(bulkhead) => bulkhead.acquire().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn acquire_async(&self) -> Result<Option<Permit>, Error> {
+        match &self.bulkhead {
+            Some(bulkhead) => bulkhead.acquire_async().await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn context<'a, E>(&self, attempt: usize, started_at: Instant, previous_error: Option<&'a E>) -> Context<'a, E> {
+        Context::new(attempt, started_at.elapsed(), previous_error, Some(self.state.clone()))
+    }
+}
+
+fn keep_retry_budget(current: Option<RetryBudget>, configured: Option<RetryBudget>) -> Option<RetryBudget> {
+    match (current, configured) {
+        (Some(current), Some(retry_budget)) if current.has_same_limits(&retry_budget) => Some(current),
+        (_, retry_budget) => retry_budget,
     }
 }
 
 impl Display for CircuitBreaker {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = self.name() {
+            write!(f, "Name: {}, ", name)?;
+        }
         write!(
             f,
             "Attempts: {}/{}, Failures: {}/{}, State: {:?}",
@@ -199,6 +904,10 @@ impl Display for CircuitBreaker {
             self.failure_count,
             self.failure_threshold,
             self.state
-        )
+        )?;
+        if let Some(manual_override) = &self.manual_override {
+            write!(f, ", Override: {:?}", manual_override)?;
+        }
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,216 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder};
+use crate::error::{Error, ErrorKind};
+use crate::state_store::StateStore;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+type Factory = dyn Fn(&str) -> Arc<dyn StateStore> + Send + Sync;
+
+#[derive(Clone)]
+struct StateStoreFactory(Arc<Factory>);
+
+impl Debug for StateStoreFactory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StateStoreFactory")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerMapBuilder {
+    template: Option<CircuitBreakerBuilder>,
+    state_store_factory: Option<StateStoreFactory>,
+    max_entries: Option<usize>,
+    idle_timeout: Option<Duration>,
+}
+
+impl Default for CircuitBreakerMapBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBreakerMapBuilder {
+    pub fn new() -> Self {
+        Self {
+            template: None,
+            state_store_factory: None,
+            max_entries: None,
+            idle_timeout: None,
+        }
+    }
+
+    pub fn with_template(&mut self, template: CircuitBreakerBuilder) -> &mut Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub fn with_state_store_factory<F>(&mut self, factory: F) -> &mut Self
+    where
+        F: Fn(&str) -> Arc<dyn StateStore> + Send + Sync + 'static,
+    {
+        self.state_store_factory = Some(StateStoreFactory(Arc::new(factory)));
+        self
+    }
+
+    pub fn with_max_entries(&mut self, max_entries: usize) -> &mut Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    pub fn with_idle_timeout(&mut self, idle_timeout: Duration) -> &mut Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    pub fn build<K>(&self) -> Result<CircuitBreakerMap<K>, Error> {
+        let template = match &self.template {
+            Some(template) => template,
+            None => return Err(Error::new(ErrorKind::Configuration, "Circuit breaker template is required".to_string())),
+        };
+        if template.has_state_store() {
+            return Err(Error::new(
+                ErrorKind::Configuration,
+                "Circuit breaker template must not have a state store. Call 'with_state_store_factory' method".to_string(),
+            ))
+        }
+        template.build()?;
+        if self.max_entries == Some(0) {
+            return Err(Error::new(ErrorKind::Configuration, "Max entries must be greater than zero".to_string()))
+        }
+        Ok(CircuitBreakerMap {
+            template: template.clone(),
+            state_store_factory: self.state_store_factory.clone(),
+            max_entries: self.max_entries,
+            idle_timeout: self.idle_timeout,
+            entries: Arc::new(Mutex::new(Entries {
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                clock: 0,
+            })),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    last_used: u64,
+    last_used_at: Instant,
+}
+
+#[derive(Debug)]
+struct Entries<K> {
+    entries: HashMap<K, Entry>,
+    recency: BTreeMap<u64, K>,
+    clock: u64,
+}
+
+impl<K> Entries<K>
+where
+    K: Eq + Hash + Clone,
+{
+    fn touch(&mut self, key: &K, now: Instant) -> Option<Arc<Mutex<CircuitBreaker>>> {
+        let entry = self.entries.get_mut(key)?;
+        self.clock += 1;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.clock, key.clone());
+        entry.last_used = self.clock;
+        entry.last_used_at = now;
+        Some(entry.circuit_breaker.clone())
+    }
+
+    fn insert(&mut self, key: K, circuit_breaker: Arc<Mutex<CircuitBreaker>>, now: Instant) {
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(key, Entry {
+            circuit_breaker,
+            last_used: self.clock,
+            last_used_at: now,
+        });
+    }
+
+    fn remove(&mut self, key: &K) -> Option<Arc<Mutex<CircuitBreaker>>> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        Some(entry.circuit_breaker)
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            self.entries.remove(&key);
+        }
+    }
+
+    fn evict_idle(&mut self, idle_timeout: Duration, now: Instant) {
+        while let Some((_, key)) = self.recency.first_key_value() {
+            if now.duration_since(self.entries[key].last_used_at) < idle_timeout {
+                break;
+            }
+            self.evict_least_recently_used();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerMap<K> {
+    template: CircuitBreakerBuilder,
+    state_store_factory: Option<StateStoreFactory>,
+    max_entries: Option<usize>,
+    idle_timeout: Option<Duration>,
+    entries: Arc<Mutex<Entries<K>>>,
+}
+
+impl<K> CircuitBreakerMap<K>
+where
+    K: Eq + Hash + Clone + Display,
+{
+    pub fn get(&self, key: &K) -> Arc<Mutex<CircuitBreaker>> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        if let Some(idle_timeout) = self.idle_timeout {
+            entries.evict_idle(idle_timeout, now);
+        }
+        if let Some(circuit_breaker) = entries.touch(key, now) {
+            return circuit_breaker;
+        }
+        if let Some(max_entries) = self.max_entries {
+            while entries.entries.len() >= max_entries {
+                entries.evict_least_recently_used();
+            }
+        }
+        let name = key.to_string();
+        let mut builder = self.template.for_key(&name);
+        if let Some(StateStoreFactory(factory)) = &self.state_store_factory {
+            builder.with_state_store(factory(&name));
+        }
+        let circuit_breaker = builder.build().expect("Circuit breaker template is validated when the map is built");
+        let circuit_breaker = Arc::new(Mutex::new(circuit_breaker));
+        entries.insert(key.clone(), circuit_breaker.clone(), now);
+        circuit_breaker
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.lock().unwrap().entries.contains_key(key)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<Arc<Mutex<CircuitBreaker>>> {
+        self.entries.lock().unwrap().remove(key)
+    }
+
+    pub fn evict_idle(&self) {
+        if let Some(idle_timeout) = self.idle_timeout {
+            self.entries.lock().unwrap().evict_idle(idle_timeout, Instant::now());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
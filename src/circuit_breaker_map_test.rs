@@ -0,0 +1,138 @@
+use crate::circuit_breaker::{CircuitBreakerBuilder, State};
+use crate::circuit_breaker_map::{CircuitBreakerMap, CircuitBreakerMapBuilder};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{circuit_breaker_builder, TIME};
+use crate::state_store::{InMemoryStateStore, StateStore};
+use rstest::rstest;
+use std::sync::Arc;
+
+#[rstest]
+fn creates_circuit_breaker_per_key(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map: CircuitBreakerMap<&str> = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .build()
+        .unwrap();
+    let tenant = map.get(&"tenant-a");
+    assert!(Arc::ptr_eq(&tenant, &map.get(&"tenant-a")));
+    assert!(!Arc::ptr_eq(&tenant, &map.get(&"tenant-b")));
+    tenant.lock().unwrap().open();
+    assert_eq!(State::Closed, *map.get(&"tenant-b").lock().unwrap().state());
+    assert_eq!(2, map.len());
+}
+
+#[rstest]
+fn names_circuit_breaker_after_key(mut circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder.with_name("payments-api").clone())
+        .build()
+        .unwrap();
+    assert_eq!(Some("payments-api/tenant-a"), map.get(&"tenant-a").lock().unwrap().name());
+    assert_eq!(Some("payments-api/tenant-b"), map.get(&"tenant-b").lock().unwrap().name());
+}
+
+#[rstest]
+fn isolates_state_stores_per_key(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .with_state_store_factory(|_| Arc::new(InMemoryStateStore::new()) as Arc<dyn StateStore>)
+        .build()
+        .unwrap();
+    map.get(&"tenant-a").lock().unwrap().record_failure();
+    map.get(&"tenant-a").lock().unwrap().record_failure();
+    assert_eq!(State::Open, *map.get(&"tenant-a").lock().unwrap().state());
+    let tenant = map.get(&"tenant-b");
+    let mut tenant = tenant.lock().unwrap();
+    assert!(tenant.retry(&mut || Ok::<(), Error>(())).is_ok());
+    assert_eq!(State::Closed, *tenant.state());
+}
+
+#[rstest]
+fn rejects_template_with_state_store(mut circuit_breaker_builder: CircuitBreakerBuilder) {
+    let error = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder.with_state_store(Arc::new(InMemoryStateStore::new())).clone())
+        .build::<String>()
+        .unwrap_err();
+    assert_eq!("Circuit breaker template must not have a state store. Call 'with_state_store_factory' method", error.description);
+}
+
+#[rstest]
+fn evicts_least_recently_used(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .with_max_entries(2)
+        .build()
+        .unwrap();
+    map.get(&1);
+    map.get(&2);
+    map.get(&1);
+    map.get(&3);
+    assert!(map.contains_key(&1));
+    assert!(!map.contains_key(&2));
+    assert!(map.contains_key(&3));
+    assert_eq!(2, map.len());
+}
+
+#[rstest]
+fn evicts_least_recently_used_after_removal(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .with_max_entries(2)
+        .build()
+        .unwrap();
+    map.get(&1);
+    map.get(&2);
+    assert!(map.remove(&1).is_some());
+    map.get(&3);
+    map.get(&4);
+    assert!(!map.contains_key(&2));
+    assert!(map.contains_key(&3));
+    assert!(map.contains_key(&4));
+    assert_eq!(2, map.len());
+}
+
+#[rstest]
+fn evicts_idle_entries_on_access(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .with_idle_timeout(TIME)
+        .build()
+        .unwrap();
+    map.get(&"idle");
+    std::thread::sleep(TIME);
+    map.get(&"busy");
+    assert!(!map.contains_key(&"idle"));
+    assert_eq!(1, map.len());
+}
+
+#[rstest]
+fn evicts_idle_entries(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let map = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .with_idle_timeout(TIME)
+        .build()
+        .unwrap();
+    map.get(&"idle");
+    std::thread::sleep(TIME / 2);
+    map.get(&"busy");
+    std::thread::sleep(TIME / 2);
+    map.evict_idle();
+    assert!(!map.contains_key(&"idle"));
+    assert!(map.contains_key(&"busy"));
+}
+
+#[rstest]
+fn requires_valid_template(circuit_breaker_builder: CircuitBreakerBuilder) {
+    let error = CircuitBreakerMapBuilder::new().build::<String>().unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    let error = CircuitBreakerMapBuilder::new()
+        .with_template(CircuitBreakerBuilder::new())
+        .build::<String>()
+        .unwrap_err();
+    assert_eq!("Reset timeout is required", error.description);
+    let error = CircuitBreakerMapBuilder::new()
+        .with_template(circuit_breaker_builder)
+        .with_max_entries(0)
+        .build::<String>()
+        .unwrap_err();
+    assert_eq!("Max entries must be greater than zero", error.description);
+}
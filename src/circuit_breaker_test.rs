@@ -1,7 +1,19 @@
-use crate::circuit_breaker::{CircuitBreaker, State};
-use crate::error::Error;
-use crate::fixtures::{circuit_breaker, failed_operation, failed_operation_then_recovered_at_first_attempt, failed_operation_then_recovered_at_second_attempt, success_operation};
+use crate::backoff::BackoffBuilder;
+use crate::bulkhead::BulkheadBuilder;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder, Override, State};
+use crate::context::Context;
+use crate::error::{Error, ErrorKind, PollError};
+use crate::fallback::{Outcome, Reason};
+use crate::fixtures::{circuit_breaker, TIME, failed_operation, failed_operation_then_recovered_at_first_attempt, failed_operation_then_recovered_at_second_attempt, polled_operation_ready_at_third_attempt, success_operation};
+#[cfg(feature = "async")]
+use crate::fixtures::{circuit_breaker_async, failed_operation_async};
+use crate::retry_budget::RetryBudgetBuilder;
+use crate::snapshot::SlotSnapshot;
 use rstest::rstest;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
 
 #[rstest]
 fn with_success_operation(
@@ -49,4 +61,366 @@ fn with_failed_operation_then_recovered_at_second_attempt(
     assert_eq!(State::Closed, circuit_breaker.state);
     assert_eq!(1, circuit_breaker.failed_attempts);
     assert_eq!(2, circuit_breaker.failure_count);
-}
\ No newline at end of file
+}
+
+#[rstest]
+fn with_context(
+    mut circuit_breaker: CircuitBreaker,
+    mut failed_operation_then_recovered_at_second_attempt: impl FnMut() -> Result<(), Error>
+) {
+    let mut attempts = Vec::new();
+    let mut operation = |context: &Context<Error>| {
+        attempts.push((
+            context.attempt(),
+            context.previous_error().is_some(),
+            context.state().cloned(),
+        ));
+        failed_operation_then_recovered_at_second_attempt()
+    };
+    let result = circuit_breaker.retry_with_context(&mut operation);
+    assert!(result.is_ok());
+    assert_eq!(
+        vec![
+            (1, false, Some(State::Closed)),
+            (2, true, Some(State::Closed)),
+            (3, true, Some(State::HalfOpen)),
+            (4, true, Some(State::HalfOpen)),
+        ],
+        attempts
+    );
+}
+
+#[rstest]
+fn poll_until_ready(
+    mut polled_operation_ready_at_third_attempt: impl FnMut() -> Result<&'static str, Error>
+) {
+    let mut circuit_breaker = CircuitBreakerBuilder::new()
+        .with_failure_threshold(2)
+        .with_reset_timeout(TIME * 2)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME)
+            .with_attempts(3)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .build()
+        .unwrap();
+    let result = circuit_breaker.poll_until(
+        &mut polled_operation_ready_at_third_attempt,
+        &mut |status| *status == "READY",
+    );
+    assert_eq!("READY", result.unwrap());
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!(0, circuit_breaker.failed_attempts);
+    assert_eq!(0, circuit_breaker.failure_count);
+}
+
+#[rstest]
+fn poll_until_never_ready(
+    mut circuit_breaker: CircuitBreaker,
+    mut polled_operation_ready_at_third_attempt: impl FnMut() -> Result<&'static str, Error>
+) {
+    let result = circuit_breaker.poll_until(&mut polled_operation_ready_at_third_attempt, &mut |_| false);
+    assert!(matches!(result, Err(PollError::Exhausted("PENDING"))));
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!(0, circuit_breaker.failure_count);
+}
+
+#[rstest]
+fn poll_until_with_failed_operation(
+    mut circuit_breaker: CircuitBreaker,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let result = circuit_breaker.poll_until(&mut failed_operation, &mut |_| true);
+    match result {
+        Err(PollError::Failed(error)) => assert_eq!("Something went wrong.", error.description),
+        _ => panic!("Expected the operation error"),
+    }
+    assert_eq!(State::HalfOpen, circuit_breaker.state);
+}
+
+#[rstest]
+fn with_history(
+    mut circuit_breaker: CircuitBreaker,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let history = circuit_breaker.retry_with_history(&mut failed_operation).unwrap_err();
+    let records = history.records();
+    assert_eq!(vec![1, 2, 3, 4], records.iter().map(|record| record.attempt()).collect::<Vec<_>>());
+    assert_eq!(
+        vec![State::Closed, State::Closed, State::HalfOpen, State::HalfOpen],
+        records.iter().map(|record| record.state().clone()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![TIME, TIME * 3, TIME * 5, TIME * 5],
+        records.iter().map(|record| record.delay()).collect::<Vec<_>>()
+    );
+    assert!(records.windows(2).all(|pair| pair[0].timestamp() <= pair[1].timestamp()));
+    assert_eq!("4 attempt(s) failed, last error: Something went wrong.", history.to_string());
+}
+
+#[rstest]
+fn with_history_records_waited_delays(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut circuit_breaker = CircuitBreakerBuilder::new()
+        .with_failure_threshold(2)
+        .with_reset_timeout(TIME)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME * 10)
+            .with_max_time(TIME / 10)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .build()
+        .unwrap();
+    let history = circuit_breaker.retry_with_history(&mut failed_operation).unwrap_err();
+    assert_eq!(
+        vec![TIME / 10, TIME / 10 + TIME, TIME / 10 + TIME * 2],
+        history.records().iter().map(|record| record.delay()).collect::<Vec<_>>()
+    );
+}
+
+#[rstest]
+fn with_history_when_open(
+    mut circuit_breaker: CircuitBreaker,
+    mut success_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.open();
+    let history = circuit_breaker.retry_with_history(&mut success_operation).unwrap_err();
+    assert_eq!(1, history.records().len());
+    assert_eq!(&State::Open, history.records()[0].state());
+    assert_eq!("Circuit breaker is open", history.into_last_error().unwrap().description);
+}
+
+#[rstest]
+fn with_fallback_on_success(
+    mut circuit_breaker: CircuitBreaker,
+    mut success_operation: impl FnMut() -> Result<(), Error>
+) {
+    let outcome = circuit_breaker.retry_with_fallback(&mut success_operation, &mut |_| ());
+    assert_eq!(Outcome::Primary(()), outcome);
+}
+
+#[rstest]
+fn with_fallback_when_exhausted(
+    mut circuit_breaker: CircuitBreaker,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let mut operation = || failed_operation().map(|_| "fresh".to_string());
+    let outcome = circuit_breaker.retry_with_fallback(&mut operation, &mut |reason| match reason {
+        Reason::Exhausted(error) => format!("cached ({})", error),
+        Reason::Rejected(_) => "rejected".to_string(),
+    });
+    assert!(outcome.is_fallback());
+    assert_eq!("cached (Something went wrong.)", outcome.into_inner());
+}
+
+#[rstest]
+fn with_fallback_when_open(
+    mut circuit_breaker: CircuitBreaker,
+    mut success_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.open();
+    let outcome = circuit_breaker.retry_with_fallback(&mut success_operation, &mut |reason| {
+        assert!(matches!(reason, Reason::Rejected(_)));
+    });
+    assert_eq!(Outcome::Fallback(()), outcome);
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn with_fallback_when_exhausted_and_as_async(
+    mut circuit_breaker_async: CircuitBreaker,
+    mut failed_operation_async: impl FnMut() -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static>>,
+) {
+    let outcome = circuit_breaker_async.retry_with_fallback_async(
+        &mut failed_operation_async,
+        &mut |reason| async move {
+            assert!(matches!(reason, Reason::Exhausted(_)));
+        },
+    ).await;
+    assert!(outcome.is_fallback());
+}
+
+#[rstest]
+fn open_after_recorded_failures_then_expires(
+    mut circuit_breaker: CircuitBreaker,
+    mut success_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.record_failure();
+    assert_eq!(State::Closed, circuit_breaker.state);
+    circuit_breaker.record_failure();
+    assert_eq!(State::Open, circuit_breaker.state);
+    let error = circuit_breaker.retry(&mut success_operation).unwrap_err();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    std::thread::sleep(TIME * 2);
+    assert!(circuit_breaker.retry(&mut success_operation).is_ok());
+    assert_eq!(State::Closed, circuit_breaker.state);
+}
+
+#[rstest]
+fn recorded_success_closes_and_clears_failures(mut circuit_breaker: CircuitBreaker) {
+    circuit_breaker.record_failure();
+    assert_eq!(1, circuit_breaker.failure_count);
+    circuit_breaker.record_success();
+    assert_eq!(0, circuit_breaker.failure_count);
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    assert_eq!(State::Open, circuit_breaker.state);
+    circuit_breaker.record_success();
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!(None, circuit_breaker.open_remaining());
+}
+
+#[rstest]
+fn open_rejects_only_until_reset_timeout(
+    mut circuit_breaker: CircuitBreaker,
+    mut success_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.open();
+    assert!(circuit_breaker.open_remaining().is_some_and(|remaining| remaining <= TIME * 2));
+    assert_eq!(ErrorKind::CircuitOpen, circuit_breaker.retry(&mut success_operation).unwrap_err().kind());
+    std::thread::sleep(TIME * 2);
+    assert_eq!(None, circuit_breaker.open_remaining());
+    assert_eq!(State::Open, circuit_breaker.state);
+    assert!(circuit_breaker.retry(&mut success_operation).is_ok());
+    assert_eq!(State::Closed, circuit_breaker.state);
+}
+
+#[rstest]
+fn forced_open_always_rejects(
+    mut circuit_breaker: CircuitBreaker,
+    mut success_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.force_open();
+    let error = circuit_breaker.retry(&mut success_operation).unwrap_err();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    assert_eq!("Circuit breaker is forced open", error.description);
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!("Attempts: 0/2, Failures: 0/2, State: Closed, Override: ForcedOpen", circuit_breaker.to_string());
+    circuit_breaker.clear_override();
+    assert!(circuit_breaker.retry(&mut success_operation).is_ok());
+}
+
+#[rstest]
+fn forced_closed_never_trips(
+    mut circuit_breaker: CircuitBreaker,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.force_closed();
+    assert!(circuit_breaker.retry(&mut failed_operation).is_err());
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!(2, circuit_breaker.failure_count);
+    circuit_breaker.record_failure();
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!(3, circuit_breaker.failure_count);
+}
+
+#[rstest]
+fn disabled_passes_through(
+    mut circuit_breaker: CircuitBreaker,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    circuit_breaker.disable();
+    assert_eq!(Some(&Override::Disabled), circuit_breaker.manual_override());
+    let mut calls = 0;
+    let error = circuit_breaker.retry(&mut || {
+        calls += 1;
+        failed_operation()
+    }).unwrap_err();
+    assert_eq!(ErrorKind::Operation, error.kind());
+    assert_eq!(1, calls);
+    circuit_breaker.record_failure();
+    assert_eq!(2, circuit_breaker.failure_count);
+}
+
+#[rstest]
+fn reconfigure_keeps_state_and_counters(mut circuit_breaker: CircuitBreaker) {
+    circuit_breaker.record_failure();
+    let mut builder = CircuitBreakerBuilder::new();
+    builder
+        .with_name("payments-api")
+        .with_attempts(3)
+        .with_failure_threshold(5)
+        .with_reset_timeout(TIME * 4)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        );
+    circuit_breaker.reconfigure(&builder).unwrap();
+    assert_eq!(State::Closed, circuit_breaker.state);
+    assert_eq!(1, circuit_breaker.failure_count);
+    assert_eq!("Name: payments-api, Attempts: 0/3, Failures: 1/5, State: Closed", circuit_breaker.to_string());
+    builder.with_failure_threshold(2);
+    circuit_breaker.reconfigure(&builder).unwrap();
+    circuit_breaker.record_failure();
+    assert_eq!(State::Open, circuit_breaker.state);
+}
+
+#[rstest]
+fn reconfigure_keeps_unchanged_bulkhead_and_retry_budget() {
+    let bulkhead = BulkheadBuilder::new().with_max_concurrent_calls(1).build().unwrap();
+    let retry_budget = RetryBudgetBuilder::new().build().unwrap();
+    let mut builder = CircuitBreakerBuilder::new();
+    builder
+        .with_reset_timeout(TIME)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .with_bulkhead(bulkhead.clone())
+        .with_retry_budget(retry_budget.clone());
+    let mut circuit_breaker = builder.build().unwrap();
+    let _permit = bulkhead.acquire().unwrap();
+    retry_budget.deposit();
+    builder
+        .with_bulkhead(BulkheadBuilder::new().with_max_concurrent_calls(1).build().unwrap())
+        .with_retry_budget(RetryBudgetBuilder::new().build().unwrap());
+    circuit_breaker.reconfigure(&builder).unwrap();
+    let error = circuit_breaker.retry(&mut || Ok::<(), Error>(())).unwrap_err();
+    assert_eq!(ErrorKind::BulkheadFull, error.kind());
+    builder.with_bulkhead(BulkheadBuilder::new().with_max_concurrent_calls(2).build().unwrap());
+    circuit_breaker.reconfigure(&builder).unwrap();
+    assert!(circuit_breaker.retry(&mut || Ok::<(), Error>(())).is_ok());
+    let deposits = |slots: Vec<SlotSnapshot>| slots.iter().map(|slot| slot.deposits).sum::<usize>();
+    assert_eq!(2, deposits(retry_budget.snapshot()));
+    assert_eq!(2, deposits(circuit_breaker.snapshot().retry_budget));
+}
+
+#[rstest]
+fn reconfigure_rejects_invalid_configuration(mut circuit_breaker: CircuitBreaker) {
+    let error = circuit_breaker.reconfigure(&CircuitBreakerBuilder::new()).unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!("Attempts: 0/2, Failures: 0/2, State: Closed", circuit_breaker.to_string());
+}
+
+#[rstest]
+fn invalid_values_in_builder() {
+    let backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let error = CircuitBreakerBuilder::new()
+        .with_reset_timeout(TIME)
+        .with_backoff(backoff.clone())
+        .with_attempts(0)
+        .build()
+        .unwrap_err();
+    assert_eq!("Attempts must be greater than zero", error.description);
+    let error = CircuitBreakerBuilder::new()
+        .with_reset_timeout(TIME)
+        .with_backoff(backoff)
+        .with_failure_threshold(1)
+        .build()
+        .unwrap_err();
+    assert_eq!("Failure threshold must be greater than one", error.description);
+}
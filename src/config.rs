@@ -0,0 +1,250 @@
+use crate::backoff::{Backoff, BackoffBuilder, Jitter};
+use crate::bulkhead::BulkheadBuilder;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder};
+use crate::error::{Error, ErrorKind};
+use crate::retry_budget::RetryBudgetBuilder;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TimeConfig {
+    Constant {
+        #[serde(with = "crate::duration::serde")]
+        duration: Duration,
+    },
+    Exponential {
+        #[serde(with = "crate::duration::serde")]
+        duration: Duration,
+        factor: f32,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitConfig {
+    #[default]
+    Synchronous,
+    Asynchronous,
+    SpinLoop,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryBudgetConfig {
+    #[serde(default, with = "crate::duration::serde::option", skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_retries_per_second: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent_can_retry: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BulkheadConfig {
+    pub max_concurrent_calls: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_queue_size: Option<usize>,
+    #[serde(default, with = "crate::duration::serde::option", skip_serializing_if = "Option::is_none")]
+    pub queue_timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackoffConfig {
+    pub time: TimeConfig,
+    #[serde(default)]
+    pub wait: WaitConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<usize>,
+    #[serde(default, with = "crate::duration::serde::option", skip_serializing_if = "Option::is_none")]
+    pub max_time: Option<Duration>,
+    #[serde(default)]
+    pub jitter: Jitter,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<RetryBudgetConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<usize>,
+    #[serde(with = "crate::duration::serde")]
+    pub reset_timeout: Duration,
+    pub backoff: BackoffConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bulkhead: Option<BulkheadConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<RetryBudgetConfig>,
+}
+
+fn path(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", prefix, field)
+    }
+}
+
+fn invalid(path: String, description: &str) -> Error {
+    let description = if path.is_empty() {
+        description.to_string()
+    } else {
+        format!("{}: {}", path, description)
+    };
+    Error::new(ErrorKind::Configuration, description)
+}
+
+fn retry_budget_builder(config: &RetryBudgetConfig, prefix: &str) -> Result<RetryBudgetBuilder, Error> {
+    let mut builder = RetryBudgetBuilder::new();
+    if let Some(ttl) = config.ttl {
+        builder.with_ttl(ttl);
+    }
+    if let Some(retries) = config.min_retries_per_second {
+        builder.with_min_retries_per_second(retries);
+    }
+    if let Some(percent) = config.percent_can_retry {
+        builder.with_percent_can_retry(percent);
+    }
+    builder.build().map_err(|error| invalid(prefix.to_string(), &error.description))?;
+    Ok(builder)
+}
+
+fn bulkhead_builder(config: &BulkheadConfig, prefix: &str) -> Result<BulkheadBuilder, Error> {
+    if config.max_concurrent_calls == 0 {
+        return Err(invalid(path(prefix, "max_concurrent_calls"), "Max concurrent calls must be greater than zero"));
+    }
+    let mut builder = BulkheadBuilder::new();
+    builder.with_max_concurrent_calls(config.max_concurrent_calls);
+    if let Some(size) = config.max_queue_size {
+        builder.with_max_queue_size(size);
+    }
+    if let Some(timeout) = config.queue_timeout {
+        builder.with_queue_timeout(timeout);
+    }
+    Ok(builder)
+}
+
+fn backoff_builder(config: &BackoffConfig, prefix: &str) -> Result<BackoffBuilder, Error> {
+    let mut builder = BackoffBuilder::new();
+    match &config.time {
+        TimeConfig::Constant { duration } => builder.with_constant_time(*duration),
+        TimeConfig::Exponential { duration, factor } => {
+            if *factor <= 0.0 {
+                return Err(invalid(path(prefix, "time.factor"), "Factor must be greater than zero"));
+            }
+            builder.with_exponential_time(*duration, *factor)
+        }
+    };
+    match config.wait {
+        WaitConfig::Synchronous => builder.as_synchronous(),
+        #[cfg(feature = "async")]
+        WaitConfig::Asynchronous => builder.as_asynchronous(),
+        #[cfg(not(feature = "async"))]
+        WaitConfig::Asynchronous => {
+            return Err(invalid(path(prefix, "wait"), "Asynchronous wait requires the 'async' feature"));
+        }
+        WaitConfig::SpinLoop => builder.as_spin_loop(),
+    };
+    if let Some(attempts) = config.attempts {
+        if attempts == 0 {
+            return Err(invalid(path(prefix, "attempts"), "Attempts must be greater than zero"));
+        }
+        builder.with_attempts(attempts);
+    }
+    if let Some(max_time) = config.max_time {
+        builder.with_max_time(max_time);
+    }
+    if let Jitter::Proportional(ratio) = config.jitter {
+        if !(0.0..=1.0).contains(&ratio) {
+            return Err(invalid(path(prefix, "jitter"), "Jitter ratio must be between 0.0 and 1.0"));
+        }
+    }
+    builder.with_jitter(config.jitter);
+    if let Some(retry_budget) = &config.retry_budget {
+        builder.with_retry_budget(retry_budget_builder(retry_budget, &path(prefix, "retry_budget"))?.build()?);
+    }
+    Ok(builder)
+}
+
+fn circuit_breaker_builder(config: &CircuitBreakerConfig) -> Result<CircuitBreakerBuilder, Error> {
+    let mut builder = CircuitBreakerBuilder::new();
+    if let Some(name) = &config.name {
+        builder.with_name(name);
+    }
+    if let Some(attempts) = config.attempts {
+        if attempts == 0 {
+            return Err(invalid("attempts".to_string(), "Attempts must be greater than zero"));
+        }
+        builder.with_attempts(attempts);
+    }
+    if let Some(threshold) = config.failure_threshold {
+        if threshold < 2 {
+            return Err(invalid("failure_threshold".to_string(), "Failure threshold must be greater than one"));
+        }
+        builder.with_failure_threshold(threshold);
+    }
+    builder.with_reset_timeout(config.reset_timeout);
+    builder.with_backoff(backoff_builder(&config.backoff, "backoff")?.build()?);
+    if let Some(bulkhead) = &config.bulkhead {
+        builder.with_bulkhead(bulkhead_builder(bulkhead, "bulkhead")?.build()?);
+    }
+    if let Some(retry_budget) = &config.retry_budget {
+        builder.with_retry_budget(retry_budget_builder(retry_budget, "retry_budget")?.build()?);
+    }
+    Ok(builder)
+}
+
+impl TryFrom<BackoffConfig> for BackoffBuilder {
+    type Error = Error;
+
+    fn try_from(config: BackoffConfig) -> Result<Self, Self::Error> {
+        backoff_builder(&config, "")
+    }
+}
+
+impl TryFrom<BackoffConfig> for Backoff {
+    type Error = Error;
+
+    fn try_from(config: BackoffConfig) -> Result<Self, Self::Error> {
+        backoff_builder(&config, "")?.build()
+    }
+}
+
+impl TryFrom<CircuitBreakerConfig> for CircuitBreakerBuilder {
+    type Error = Error;
+
+    fn try_from(config: CircuitBreakerConfig) -> Result<Self, Self::Error> {
+        circuit_breaker_builder(&config)
+    }
+}
+
+impl TryFrom<CircuitBreakerConfig> for CircuitBreaker {
+    type Error = Error;
+
+    fn try_from(config: CircuitBreakerConfig) -> Result<Self, Self::Error> {
+        circuit_breaker_builder(&config)?.build()
+    }
+}
+
+impl TryFrom<RetryBudgetConfig> for RetryBudgetBuilder {
+    type Error = Error;
+
+    fn try_from(config: RetryBudgetConfig) -> Result<Self, Self::Error> {
+        retry_budget_builder(&config, "")
+    }
+}
+
+impl TryFrom<BulkheadConfig> for BulkheadBuilder {
+    type Error = Error;
+
+    fn try_from(config: BulkheadConfig) -> Result<Self, Self::Error> {
+        bulkhead_builder(&config, "")
+    }
+}
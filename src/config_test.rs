@@ -0,0 +1,90 @@
+use crate::backoff::Backoff;
+use crate::circuit_breaker::{CircuitBreaker, State};
+use crate::config::{BackoffConfig, CircuitBreakerConfig, TimeConfig, WaitConfig};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::failed_operation;
+use rstest::rstest;
+use std::time::Duration;
+
+const CONFIG: &str = r#"
+name = "payments-api"
+attempts = 2
+failure_threshold = 3
+reset_timeout = "50ms"
+
+[backoff]
+attempts = 4
+time = { type = "exponential", duration = "5ms", factor = 2.0 }
+
+[backoff.retry_budget]
+ttl = "10s"
+percent_can_retry = 0.5
+
+[bulkhead]
+max_concurrent_calls = 8
+queue_timeout = "1s"
+"#;
+
+#[rstest]
+fn deserializes_from_toml() {
+    let config: CircuitBreakerConfig = toml::from_str(CONFIG).unwrap();
+    assert_eq!(Some("payments-api".to_string()), config.name);
+    assert_eq!(Duration::from_millis(50), config.reset_timeout);
+    assert_eq!(
+        TimeConfig::Exponential { duration: Duration::from_millis(5), factor: 2.0 },
+        config.backoff.time
+    );
+    assert_eq!(WaitConfig::Synchronous, config.backoff.wait);
+    assert_eq!(Some(Duration::from_secs(10)), config.backoff.retry_budget.as_ref().unwrap().ttl);
+    assert_eq!(Some(Duration::from_secs(1)), config.bulkhead.as_ref().unwrap().queue_timeout);
+}
+
+#[rstest]
+fn builds_circuit_breaker_from_config(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let config: CircuitBreakerConfig = toml::from_str(CONFIG).unwrap();
+    let mut circuit_breaker = CircuitBreaker::try_from(config).unwrap();
+    assert_eq!(Some("payments-api"), circuit_breaker.name());
+    assert!(circuit_breaker.retry(&mut failed_operation).is_err());
+    assert_eq!(State::HalfOpen, *circuit_breaker.state());
+    assert_eq!("Name: payments-api, Attempts: 2/2, Failures: 3/3, State: HalfOpen", circuit_breaker.to_string());
+}
+
+#[rstest]
+fn serializes_humane_durations() {
+    let config: CircuitBreakerConfig = toml::from_str(CONFIG).unwrap();
+    let serialized = toml::to_string(&config).unwrap();
+    assert!(serialized.contains("reset_timeout = \"50ms\""));
+    assert_eq!(config, toml::from_str(&serialized).unwrap());
+}
+
+#[rstest]
+#[case("attempts = 0\nreset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"1s\" } }", "attempts: Attempts must be greater than zero")]
+#[case("failure_threshold = 1\nreset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"1s\" } }", "failure_threshold: Failure threshold must be greater than one")]
+#[case("reset_timeout = \"1s\"\nbackoff = { attempts = 0, time = { type = \"constant\", duration = \"1s\" } }", "backoff.attempts: Attempts must be greater than zero")]
+#[case("reset_timeout = \"1s\"\nbackoff = { time = { type = \"exponential\", duration = \"1s\", factor = 0.0 } }", "backoff.time.factor: Factor must be greater than zero")]
+#[case("reset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"1s\" }, jitter = { proportional = 1.5 } }", "backoff.jitter: Jitter ratio must be between 0.0 and 1.0")]
+#[case("reset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"1s\" }, retry_budget = { ttl = \"2m\" } }", "backoff.retry_budget: TTL must be between 1 and 60 seconds")]
+#[case("reset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"1s\" } }\nbulkhead = { max_concurrent_calls = 0 }", "bulkhead.max_concurrent_calls: Max concurrent calls must be greater than zero")]
+fn reports_field_paths(#[case] config: &str, #[case] description: &str) {
+    let config: CircuitBreakerConfig = toml::from_str(config).unwrap();
+    let error = CircuitBreaker::try_from(config).unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!(description, error.description);
+}
+
+#[rstest]
+#[case("\"250ms\"", Duration::from_millis(250))]
+#[case("\"5s\"", Duration::from_secs(5))]
+#[case("\"1m30s\"", Duration::from_secs(90))]
+#[case("\"1.5s\"", Duration::from_millis(1500))]
+fn parses_humane_durations(#[case] duration: &str, #[case] expected: Duration) {
+    let config: BackoffConfig = toml::from_str(&format!("time = {{ type = \"constant\", duration = {} }}", duration)).unwrap();
+    assert_eq!(TimeConfig::Constant { duration: expected }, config.time);
+    assert!(Backoff::try_from(config).is_ok());
+}
+
+#[rstest]
+fn rejects_invalid_durations() {
+    let error = toml::from_str::<BackoffConfig>("time = { type = \"constant\", duration = \"5 parsecs\" }").unwrap_err();
+    assert!(error.to_string().contains("Invalid duration unit 'parsecs'"));
+}
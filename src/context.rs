@@ -0,0 +1,42 @@
+use crate::circuit_breaker::State;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct Context<'a, E> {
+    attempt: usize,
+    elapsed: Duration,
+    previous_error: Option<&'a E>,
+    state: Option<State>,
+}
+
+impl<'a, E> Context<'a, E> {
+    pub(crate) fn new(
+        attempt: usize,
+        elapsed: Duration,
+        previous_error: Option<&'a E>,
+        state: Option<State>,
+    ) -> Self {
+        Self {
+            attempt,
+            elapsed,
+            previous_error,
+            state,
+        }
+    }
+
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn previous_error(&self) -> Option<&'a E> {
+        self.previous_error
+    }
+
+    pub fn state(&self) -> Option<&State> {
+        self.state.as_ref()
+    }
+}
@@ -0,0 +1,231 @@
+use crate::backoff::{BackoffBuilder, Jitter};
+use crate::duration;
+use crate::error::{Error, ErrorKind};
+use crate::retry_budget::{RetryBudget, RetryBudgetBuilder};
+use std::time::Duration;
+
+struct Argument<'a> {
+    key: Option<&'a str>,
+    value: &'a str,
+    column: usize,
+    value_column: usize,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+fn error(description: String, column: usize) -> Error {
+    Error::new(ErrorKind::Configuration, format!("{} at column {}", description, column))
+}
+
+impl<'a> Parser<'a> {
+    fn column(&self) -> usize {
+        self.position + 1
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(error(format!("Expected '{}' but found '{}'", expected, c), self.column())),
+            None => Err(error(format!("Expected '{}' but reached the end", expected), self.column())),
+        }
+    }
+
+    fn identifier(&mut self) -> Result<(&'a str, usize), Error> {
+        self.skip_whitespace();
+        let start = self.position;
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphabetic()) {
+            self.position += c.len_utf8();
+        }
+        if start == self.position {
+            return Err(error("Expected a backoff kind such as 'const' or 'exp'".to_string(), start + 1));
+        }
+        Ok((&self.input[start..self.position], start + 1))
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Argument<'a>>, Error> {
+        let mut arguments = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            while let Some(c) = self.peek().filter(|c| *c != ',' && *c != ')') {
+                self.position += c.len_utf8();
+            }
+            let raw = &self.input[start..self.position];
+            let trimmed = raw.trim_end();
+            if trimmed.is_empty() {
+                return Err(error("Expected an argument".to_string(), start + 1));
+            }
+            arguments.push(match trimmed.split_once('=') {
+                Some((key, value)) => {
+                    let value_start = start + key.len() + 1;
+                    let value_offset = value.len() - value.trim_start().len();
+                    Argument {
+                        key: Some(key.trim()),
+                        value: value.trim(),
+                        column: start + 1,
+                        value_column: value_start + value_offset + 1,
+                    }
+                }
+                None => Argument {
+                    key: None,
+                    value: trimmed,
+                    column: start + 1,
+                    value_column: start + 1,
+                },
+            });
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(')') => {
+                    self.position += 1;
+                    return Ok(arguments);
+                }
+                _ => return Err(error("Expected ',' or ')' but reached the end".to_string(), self.column())),
+            }
+        }
+    }
+}
+
+fn parse_duration(argument: &Argument) -> Result<Duration, Error> {
+    duration::parse(argument.value).map_err(|description| error(description, argument.value_column))
+}
+
+fn parse_retry_budget(argument: &Argument) -> Result<RetryBudget, Error> {
+    let invalid = || error(
+        format!("Invalid budget '{}', expected 'window:<ttl>:<min retries>:<percent>' or 'tokens:<capacity>:<retry cost>:<timeout cost>'", argument.value),
+        argument.value_column,
+    );
+    let parts: Vec<&str> = argument.value.split(':').map(str::trim).collect();
+    let mut builder = RetryBudgetBuilder::new();
+    match parts.as_slice() {
+        ["window", ttl, min_retries, percent] => {
+            builder
+                .with_ttl(duration::parse(ttl).map_err(|_| invalid())?)
+                .with_min_retries_per_second(min_retries.parse().map_err(|_| invalid())?)
+                .with_percent_can_retry(percent.parse().map_err(|_| invalid())?);
+        }
+        ["tokens", capacity, retry_cost, timeout_cost] => {
+            builder.with_token_bucket(
+                capacity.parse().map_err(|_| invalid())?,
+                retry_cost.parse().map_err(|_| invalid())?,
+                timeout_cost.parse().map_err(|_| invalid())?,
+            );
+        }
+        _ => return Err(invalid()),
+    }
+    builder.build().map_err(|cause| error(cause.description, argument.value_column))
+}
+
+pub(crate) fn parse(input: &str) -> Result<BackoffBuilder, Error> {
+    let mut parser = Parser {
+        input,
+        position: 0,
+    };
+    let (kind, kind_column) = parser.identifier()?;
+    parser.expect('(')?;
+    let arguments_column = parser.column();
+    let arguments = parser.arguments()?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(error("Unexpected trailing input".to_string(), parser.column()));
+    }
+    let positional: Vec<&Argument> = arguments.iter().take_while(|argument| argument.key.is_none()).collect();
+    let mut builder = BackoffBuilder::new();
+    builder.as_synchronous();
+    match kind {
+        "const" | "constant" => {
+            match positional.as_slice() {
+                [duration] => builder.with_constant_time(parse_duration(duration)?),
+                [] => return Err(error("Expected a duration".to_string(), arguments_column)),
+                [_, extra, ..] => return Err(error("Unexpected argument".to_string(), extra.column)),
+            };
+        }
+        "exp" | "exponential" => {
+            match positional.as_slice() {
+                [duration, factor] => {
+                    let value = factor.value.strip_prefix('x')
+                        .ok_or_else(|| error("Expected a factor such as 'x2'".to_string(), factor.column))?;
+                    let value: f32 = value.parse()
+                        .ok()
+                        .filter(|value: &f32| *value > 0.0)
+                        .ok_or_else(|| error("Factor must be a number greater than zero".to_string(), factor.column + 1))?;
+                    builder.with_exponential_time(parse_duration(duration)?, value)
+                }
+                [_, _, extra, ..] => return Err(error("Unexpected argument".to_string(), extra.column)),
+                _ => return Err(error("Expected a duration and a factor".to_string(), arguments_column)),
+            };
+        }
+        _ => return Err(error(format!("Unknown backoff '{}', expected 'const' or 'exp'", kind), kind_column)),
+    }
+    for argument in &arguments[positional.len()..] {
+        match argument.key {
+            Some("max") => {
+                builder.with_max_time(parse_duration(argument)?);
+            }
+            Some("jitter") => {
+                builder.with_jitter(match argument.value {
+                    "none" => Jitter::None,
+                    "full" => Jitter::Full,
+                    "equal" => Jitter::Equal,
+                    value if value.starts_with(|c: char| c.is_ascii_digit()) => {
+                        let ratio = value.parse::<f32>()
+                            .ok()
+                            .filter(|ratio| (0.0..=1.0).contains(ratio))
+                            .ok_or_else(|| error("Jitter ratio must be a number between 0 and 1".to_string(), argument.value_column))?;
+                        Jitter::Proportional(ratio)
+                    }
+                    value => return Err(error(
+                        format!("Unknown jitter '{}', expected 'none', 'full', 'equal' or a ratio", value),
+                        argument.value_column,
+                    )),
+                });
+            }
+            Some("attempts") => {
+                let attempts = argument.value.parse::<usize>()
+                    .ok()
+                    .filter(|attempts| *attempts > 0)
+                    .ok_or_else(|| error("Attempts must be a number greater than zero".to_string(), argument.value_column))?;
+                builder.with_attempts(attempts);
+            }
+            Some("budget") => {
+                builder.with_retry_budget(parse_retry_budget(argument)?);
+            }
+            Some("wait") => {
+                match argument.value {
+                    "sync" => builder.as_synchronous(),
+                    #[cfg(feature = "async")]
+                    "async" => builder.as_asynchronous(),
+                    #[cfg(not(feature = "async"))]
+                    "async" => return Err(error(
+                        "Asynchronous wait requires the 'async' feature".to_string(),
+                        argument.value_column,
+                    )),
+                    "spin" => builder.as_spin_loop(),
+                    value => return Err(error(
+                        format!("Unknown wait '{}', expected 'sync', 'async' or 'spin'", value),
+                        argument.value_column,
+                    )),
+                };
+            }
+            Some(key) => return Err(error(format!("Unknown option '{}'", key), argument.column)),
+            None => return Err(error("Positional arguments must come before options".to_string(), argument.column)),
+        }
+    }
+    Ok(builder)
+}
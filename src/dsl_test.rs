@@ -0,0 +1,84 @@
+use crate::backoff::{Backoff, BackoffBuilder};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{failed_operation, TIME};
+use crate::retry_budget::RetryBudgetBuilder;
+use rstest::rstest;
+use std::time::{Duration, Instant};
+
+#[rstest]
+#[case("const(1s)", "const(1s)")]
+#[case("constant( 250ms )", "const(250ms)")]
+#[case("exp(100ms, x2, max=10s, jitter=full, attempts=5)", "exp(100ms, x2, max=10s, jitter=full, attempts=5)")]
+#[case("exp(100ms,x1.5,jitter=equal)", "exp(100ms, x1.5, jitter=equal)")]
+#[case("exp(1s, x1.6, max=2m, jitter=0.2)", "exp(1s, x1.6, max=2m, jitter=0.2)")]
+#[case("const(1m30s, attempts=3, wait=spin)", "const(90s, attempts=3, wait=spin)")]
+#[case("exp(1s, x2, jitter=none, wait=sync)", "exp(1s, x2)")]
+#[case("const(1s, budget=window:10s:10:0.2)", "const(1s, budget=window:10s:10:0.2)")]
+#[case("const(1s, attempts=3, budget=tokens:500:5:10)", "const(1s, attempts=3, budget=tokens:500:5:10)")]
+fn round_trips_through_display(#[case] input: &str, #[case] expected: &str) {
+    let backoff: Backoff = input.parse().unwrap();
+    assert_eq!(expected, backoff.to_string());
+    assert_eq!(expected, backoff.to_string().parse::<Backoff>().unwrap().to_string());
+}
+
+#[rstest]
+#[case("", "Expected a backoff kind such as 'const' or 'exp' at column 1")]
+#[case("linear(1s)", "Unknown backoff 'linear', expected 'const' or 'exp' at column 1")]
+#[case("const 1s", "Expected '(' but found '1' at column 7")]
+#[case("const(1s", "Expected ',' or ')' but reached the end at column 9")]
+#[case("const()", "Expected an argument at column 7")]
+#[case("const(1s) extra", "Unexpected trailing input at column 11")]
+#[case("const(5 parsecs)", "Invalid duration unit 'parsecs' in '5 parsecs', expected one of h, m, s, ms, us or ns at column 7")]
+#[case("const(99999999999999999999h)", "Duration '99999999999999999999h' is too large at column 7")]
+#[case("const(5000000h 5000000h)", "Duration '5000000h 5000000h' is too large at column 7")]
+#[case("exp(100ms)", "Expected a duration and a factor at column 5")]
+#[case("exp(100ms, 2)", "Expected a factor such as 'x2' at column 12")]
+#[case("exp(100ms, x0)", "Factor must be a number greater than zero at column 13")]
+#[case("const(1s, attempts=0)", "Attempts must be a number greater than zero at column 20")]
+#[case("const(1s, jitter=some)", "Unknown jitter 'some', expected 'none', 'full', 'equal' or a ratio at column 18")]
+#[case("const(1s, jitter=1.5)", "Jitter ratio must be a number between 0 and 1 at column 18")]
+#[case("const(1s, retries=2)", "Unknown option 'retries' at column 11")]
+#[case("const(max=1s, 1s)", "Expected a duration at column 7")]
+#[case("const(1s, budget=tokens:5)", "Invalid budget 'tokens:5', expected 'window:<ttl>:<min retries>:<percent>' or 'tokens:<capacity>:<retry cost>:<timeout cost>' at column 18")]
+#[case("const(1s, budget=tokens:5:5:10)", "Token capacity must cover the retry and timeout costs at column 18")]
+fn reports_column_on_error(#[case] input: &str, #[case] description: &str) {
+    let error = input.parse::<Backoff>().unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!(description, error.description);
+}
+
+#[rstest]
+fn caps_exponential_time_at_max(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut backoff: Backoff = "exp(10ms, x10, max=30ms, attempts=4)".parse().unwrap();
+    let started_at = Instant::now();
+    assert!(backoff.retry(&mut failed_operation).is_err());
+    let elapsed = started_at.elapsed();
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_millis(400));
+    assert_eq!(Duration::from_millis(30), *Backoff::get_next(&backoff.time_strategy));
+}
+
+#[rstest]
+fn full_jitter_stays_below_delay() {
+    let mut backoff: Backoff = "exp(10ms, x2, max=1s, jitter=full, attempts=20)".parse().unwrap();
+    let mut delays = Vec::new();
+    for _ in 0..20 {
+        let delay = (*Backoff::get_next(&backoff.time_strategy)).min(Duration::from_secs(1));
+        let jittered = backoff.advance(Duration::ZERO);
+        assert!(jittered <= delay, "Jittered {:?} should not exceed {:?}", jittered, delay);
+        delays.push((jittered, delay));
+    }
+    assert!(delays.iter().any(|(jittered, delay)| jittered < delay));
+}
+
+#[rstest]
+fn retry_budget_round_trips_through_display() {
+    let mut builder = BackoffBuilder::new();
+    builder
+        .with_constant_time(TIME)
+        .with_retry_budget(RetryBudgetBuilder::new().with_token_bucket(500, 5, 10).build().unwrap())
+        .as_synchronous();
+    let backoff = builder.build().unwrap();
+    assert_eq!("const(100ms, budget=tokens:500:5:10)", backoff.to_string());
+    assert_eq!(backoff.to_string(), backoff.to_string().parse::<Backoff>().unwrap().to_string());
+}
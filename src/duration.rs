@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+const UNITS: [(&str, u128); 6] = [
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+pub(crate) fn parse(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("Duration is empty".to_string());
+    }
+    let mut nanos = 0u64;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_length = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let unit_length = rest[number_length..].find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len() - number_length);
+        let number = &rest[..number_length];
+        let unit = rest[number_length..number_length + unit_length].trim();
+        let number: f64 = number.parse()
+            .map_err(|_| format!("Invalid duration '{}', expected a number followed by a unit", value))?;
+        let factor = UNITS.iter()
+            .find(|(name, _)| *name == unit)
+            .map(|(_, factor)| *factor)
+            .ok_or_else(|| format!("Invalid duration unit '{}' in '{}', expected one of h, m, s, ms, us or ns", unit, value))?;
+        let component = (number * factor as f64).round();
+        if !component.is_finite() || component >= u64::MAX as f64 {
+            return Err(format!("Duration '{}' is too large", value));
+        }
+        nanos = nanos.checked_add(component as u64)
+            .ok_or_else(|| format!("Duration '{}' is too large", value))?;
+        rest = &rest[number_length + unit_length..];
+    }
+    Ok(Duration::from_nanos(nanos))
+}
+
+pub(crate) fn format(duration: &Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let (unit, factor) = UNITS.iter()
+        .find(|(_, factor)| nanos.is_multiple_of(*factor))
+        .unwrap();
+    format!("{}{}", nanos / factor, unit)
+}
+
+#[cfg(feature = "serde")]
+pub(crate) mod serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub(crate) fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format(duration))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse(&value).map_err(serde::de::Error::custom)
+    }
+
+    pub(crate) mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+        use std::time::Duration;
+
+        pub(crate) fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+            match duration {
+                Some(duration) => super::serialize(duration, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(value) => crate::duration::parse(&value).map(Some).map_err(serde::de::Error::custom),
+                None => Ok(None),
+            }
+        }
+    }
+}
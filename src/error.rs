@@ -1,15 +1,66 @@
 use std::error::Error as StdError;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    Operation,
+    Configuration,
+    CircuitOpen,
+    BulkheadFull,
+    RateLimited,
+    Timeout,
+    StateStore,
+}
 
 #[derive(Debug, Clone)]
 pub struct Error {
+    kind: ErrorKind,
     pub description: String,
 }
 
+impl Error {
+    pub fn new(kind: ErrorKind, description: String) -> Self {
+        Self {
+            kind,
+            description,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.description)
     }
 }
 
-impl StdError for Error {}
\ No newline at end of file
+impl StdError for Error {}
+
+pub enum PollError<O, E> {
+    Failed(E),
+    Exhausted(O),
+}
+
+impl<O, E: Debug> Debug for PollError<O, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PollError::Failed(error) => f.debug_tuple("Failed").field(error).finish(),
+            PollError::Exhausted(_) => f.debug_tuple("Exhausted").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl<O, E: Display> Display for PollError<O, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PollError::Failed(error) => write!(f, "{}", error),
+            PollError::Exhausted(_) => write!(f, "Polling condition not met"),
+        }
+    }
+}
+
+impl<O, E: StdError> StdError for PollError<O, E> {}
@@ -0,0 +1,29 @@
+use crate::error::Error;
+
+#[derive(Debug, Clone)]
+pub enum Reason<E> {
+    Rejected(Error),
+    Exhausted(E),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome<O> {
+    Primary(O),
+    Fallback(O),
+}
+
+impl<O> Outcome<O> {
+    pub fn is_primary(&self) -> bool {
+        matches!(self, Outcome::Primary(_))
+    }
+
+    pub fn is_fallback(&self) -> bool {
+        matches!(self, Outcome::Fallback(_))
+    }
+
+    pub fn into_inner(self) -> O {
+        match self {
+            Outcome::Primary(value) | Outcome::Fallback(value) => value,
+        }
+    }
+}
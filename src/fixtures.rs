@@ -1,23 +1,25 @@
-use crate::backoff::BackoffBuilder;
+use crate::backoff::{BackoffBuilder, WaitStrategy};
 use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder};
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use rstest::fixture;
+#[cfg(feature = "async")]
 use std::future::Future;
+#[cfg(feature = "async")]
 use std::pin::Pin;
 use std::time::Duration;
 
 pub const TIME: Duration = Duration::from_millis(100);
 
+#[cfg(feature = "async")]
 async fn async_succeed() -> Result<(), Error> {
     tokio::time::sleep(TIME).await;
     Ok(())
 }
 
+#[cfg(feature = "async")]
 async fn async_error() -> Result<(), Error> {
     tokio::time::sleep(TIME).await;
-    Err(Error {
-        description: "Something went wrong.".to_string(),
-    })
+    Err(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
 }
 
 #[fixture]
@@ -27,6 +29,7 @@ pub fn success_operation() -> impl FnMut() -> Result<(), Error> {
     }
 }
 
+#[cfg(feature = "async")]
 #[fixture]
 pub fn success_operation_async() -> impl FnMut() -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static >> {
     || Box::pin(async_succeed())
@@ -35,12 +38,11 @@ pub fn success_operation_async() -> impl FnMut() -> Pin<Box<dyn Future<Output =
 #[fixture]
 pub fn failed_operation() -> impl FnMut() -> Result<(), Error> {
     || {
-        Err(Error {
-            description: "Something went wrong.".to_string(),
-        })
+        Err(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
     }
 }
 
+#[cfg(feature = "async")]
 #[fixture]
 pub fn failed_operation_async() -> impl FnMut() -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static >> {
     || Box::pin(async_error())
@@ -54,9 +56,7 @@ pub fn failed_operation_then_recovered_at_first_attempt() -> impl FnMut() -> Res
             return Ok(());
         }
         attempts += 1;
-        Err(Error {
-            description: "Something went wrong.".to_string(),
-        })
+        Err(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
     }
 }
 
@@ -68,24 +68,73 @@ pub fn failed_operation_then_recovered_at_second_attempt() -> impl FnMut() -> Re
             return Ok(());
         }
         attempts += 1;
-        Err(Error {
-            description: "Something went wrong.".to_string(),
-        })
+        Err(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
     }
 }
 
 #[fixture]
-pub fn circuit_breaker() -> CircuitBreaker {
-    CircuitBreakerBuilder::new()
-        .with_attempts(2)
-        .with_failure_threshold(2)
+pub fn polled_operation_ready_at_third_attempt() -> impl FnMut() -> Result<&'static str, Error> {
+    let mut attempts = 0;
+    move || {
+        attempts += 1;
+        if attempts == 3 {
+            return Ok("READY");
+        }
+        Ok("PENDING")
+    }
+}
+
+#[fixture]
+pub fn backoff_builder(
+    #[default(TIME)] duration: Duration,
+    #[default(1)] attempts: usize,
+    #[default(WaitStrategy::Synchronous)] wait_strategy: WaitStrategy,
+) -> BackoffBuilder {
+    let mut builder = BackoffBuilder::new();
+    builder
+        .with_constant_time(duration)
+        .with_attempts(attempts);
+    match wait_strategy {
+        WaitStrategy::Synchronous => builder.as_synchronous(),
+        #[cfg(feature = "async")]
+        WaitStrategy::Asynchronous => builder.as_asynchronous(),
+        WaitStrategy::SpinLoop => builder.as_spin_loop(),
+    };
+    builder
+}
+
+#[fixture]
+pub fn circuit_breaker_builder(
+    #[default(1)] attempts: usize,
+    #[default(2)] failure_threshold: usize,
+    backoff_builder: BackoffBuilder,
+) -> CircuitBreakerBuilder {
+    let mut builder = CircuitBreakerBuilder::new();
+    builder
+        .with_attempts(attempts)
+        .with_failure_threshold(failure_threshold)
         .with_reset_timeout(TIME * 2)
-        .with_backoff(BackoffBuilder::new()
-            .with_constant_time(TIME)
-            .as_synchronous()
-            .build()
-            .unwrap()
-        )
-        .build()
-        .unwrap()
-}
\ No newline at end of file
+        .with_backoff_builder(backoff_builder);
+    builder
+}
+
+#[fixture]
+pub fn circuit_breaker(#[with(2)] circuit_breaker_builder: CircuitBreakerBuilder) -> CircuitBreaker {
+    circuit_breaker_builder.build().unwrap()
+}
+
+#[cfg(feature = "async")]
+#[fixture]
+pub fn circuit_breaker_async(
+    #[with(2, 2, backoff_builder(TIME, 1, WaitStrategy::Asynchronous))] circuit_breaker_builder: CircuitBreakerBuilder,
+) -> CircuitBreaker {
+    circuit_breaker_builder.build().unwrap()
+}
+
+#[cfg(feature = "async")]
+#[fixture]
+pub fn retrying_circuit_breaker(
+    #[with(1, 3, backoff_builder(TIME / 10, 3, WaitStrategy::Asynchronous))] circuit_breaker_builder: CircuitBreakerBuilder,
+) -> CircuitBreaker {
+    circuit_breaker_builder.build().unwrap()
+}
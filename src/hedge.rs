@@ -0,0 +1,173 @@
+use crate::circuit_breaker::CircuitBreaker;
+use crate::error::{Error, ErrorKind};
+use crate::telemetry;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+enum Event<O, E> {
+    Completed(usize, Result<O, E>),
+    Hedge,
+}
+
+#[derive(Debug, Clone)]
+pub struct HedgeBuilder {
+    delay: Option<Duration>,
+    max_hedges: usize,
+}
+
+impl Default for HedgeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HedgeBuilder {
+    pub fn new() -> Self {
+        Self {
+            delay: None,
+            max_hedges: 1,
+        }
+    }
+
+    pub fn with_delay(&mut self, delay: Duration) -> &mut Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    pub fn with_max_hedges(&mut self, max_hedges: usize) -> &mut Self {
+        self.max_hedges = max_hedges;
+        self
+    }
+
+    pub fn build(&self) -> Result<Hedge, Error> {
+        if self.delay.is_none() {
+            return Err(Error::new(ErrorKind::Configuration, "Hedge delay is required".to_string()))
+        }
+        Ok(Hedge {
+            delay: self.delay.unwrap(),
+            max_hedges: self.max_hedges,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hedge {
+    delay: Duration,
+    max_hedges: usize,
+}
+
+impl Hedge {
+    pub async fn execute<F, O, E, R>(&self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut() -> R,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.run(operation, None).await
+    }
+
+    pub async fn execute_with_circuit_breaker<F, O, E, R>(
+        &self,
+        circuit_breaker: &mut CircuitBreaker,
+        operation: &mut F,
+    ) -> Result<O, E>
+    where
+        F: FnMut() -> R,
+        E: From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        circuit_breaker.synchronize_async().await;
+        if let Some(error) = circuit_breaker.current_rejection() {
+            telemetry::rejected(circuit_breaker.name(), &error);
+            return Err(error.into());
+        }
+        self.run(operation, Some(circuit_breaker)).await
+    }
+
+    async fn run<F, O, E, R>(&self, operation: &mut F, circuit_breaker: Option<&mut CircuitBreaker>) -> Result<O, E>
+    where
+        F: FnMut() -> R,
+        R: Future<Output = Result<O, E>>,
+    {
+        let name = circuit_breaker.as_ref().and_then(|circuit_breaker| circuit_breaker.name()).map(str::to_string);
+        let span = telemetry::hedge_span(self.max_hedges);
+        let started_at = Instant::now();
+        telemetry::call_started(name.as_deref());
+        let result = telemetry::instrument(span, self.run_requests(operation, circuit_breaker, name.as_deref())).await;
+        telemetry::call_finished(name.as_deref(), started_at.elapsed());
+        result
+    }
+
+    async fn run_requests<F, O, E, R>(
+        &self,
+        operation: &mut F,
+        mut circuit_breaker: Option<&mut CircuitBreaker>,
+        name: Option<&str>,
+    ) -> Result<O, E>
+    where
+        F: FnMut() -> R,
+        R: Future<Output = Result<O, E>>,
+    {
+        telemetry::attempt_started(name, 1);
+        let mut in_flight: Vec<(usize, Pin<Box<R>>)> = vec![(1, Box::pin(operation()))];
+        let mut hedges = 0;
+        let mut max_hedges = self.max_hedges;
+        let mut last_error = None;
+        let sleep = tokio::time::sleep(self.delay);
+        tokio::pin!(sleep);
+        loop {
+            let event = poll_fn(|cx| {
+                for (index, (_, future)) in in_flight.iter_mut().enumerate() {
+                    if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                        return Poll::Ready(Event::Completed(index, result));
+                    }
+                }
+                if hedges < max_hedges && sleep.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Event::Hedge);
+                }
+                Poll::Pending
+            }).await;
+            match event {
+                Event::Completed(index, Ok(value)) => {
+                    telemetry::attempt_succeeded(name, in_flight[index].0);
+                    if let Some(circuit_breaker) = circuit_breaker.as_mut() {
+                        circuit_breaker.succeed();
+                        circuit_breaker.synchronize_async().await;
+                    }
+                    return Ok(value);
+                }
+                Event::Completed(index, Err(error)) => {
+                    let (attempt, _) = in_flight.remove(index);
+                    telemetry::attempt_classified_failed(name, attempt, Duration::ZERO);
+                    if let Some(circuit_breaker) = circuit_breaker.as_mut() {
+                        circuit_breaker.fail();
+                        circuit_breaker.synchronize_async().await;
+                        if circuit_breaker.current_rejection().is_some() {
+                            max_hedges = hedges;
+                        }
+                    }
+                    if in_flight.is_empty() && hedges >= max_hedges {
+                        return Err(error);
+                    }
+                    last_error = Some(error);
+                }
+                Event::Hedge => {
+                    if circuit_breaker.as_ref().is_some_and(|circuit_breaker| circuit_breaker.current_rejection().is_some()) {
+                        max_hedges = hedges;
+                        if in_flight.is_empty() {
+                            if let Some(error) = last_error.take() {
+                                return Err(error);
+                            }
+                        }
+                        continue;
+                    }
+                    hedges += 1;
+                    telemetry::attempt_started(name, hedges + 1);
+                    in_flight.push((hedges + 1, Box::pin(operation())));
+                    sleep.as_mut().reset(tokio::time::Instant::now() + self.delay);
+                }
+            }
+        }
+    }
+}
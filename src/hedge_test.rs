@@ -0,0 +1,111 @@
+use crate::circuit_breaker::{CircuitBreaker, State};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{circuit_breaker_async, TIME};
+use crate::hedge::HedgeBuilder;
+use rstest::rstest;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[rstest]
+fn missing_delay_in_builder() {
+    let hedge = HedgeBuilder::new()
+        .build();
+    assert_eq!("Hedge delay is required", hedge.unwrap_err().description);
+}
+
+#[rstest]
+#[tokio::test]
+async fn hedge_answers_before_slow_request() {
+    let hedge = HedgeBuilder::new()
+        .with_delay(TIME)
+        .with_max_hedges(2)
+        .build()
+        .unwrap();
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let mut requests = 0;
+    let mut operation = || {
+        requests += 1;
+        let request = requests;
+        let guard = DropCounter(dropped.clone());
+        async move {
+            let _guard = guard;
+            if request == 1 {
+                tokio::time::sleep(TIME * 10).await;
+            }
+            Ok::<_, Error>(request)
+        }
+    };
+    let started_at = Instant::now();
+    let result = hedge.execute(&mut operation).await;
+    assert_eq!(2, result.unwrap());
+    assert!(started_at.elapsed() < TIME * 5);
+    assert_eq!(2, dropped.load(Ordering::SeqCst));
+}
+
+#[rstest]
+#[tokio::test]
+async fn no_hedge_when_request_is_fast() {
+    let hedge = HedgeBuilder::new()
+        .with_delay(TIME)
+        .build()
+        .unwrap();
+    let mut requests = 0;
+    let mut operation = || {
+        requests += 1;
+        async { Ok::<_, Error>(()) }
+    };
+    assert!(hedge.execute(&mut operation).await.is_ok());
+    assert_eq!(1, requests);
+}
+
+#[rstest]
+#[tokio::test]
+async fn waits_out_delay_before_hedging_a_failure() {
+    let hedge = HedgeBuilder::new()
+        .with_delay(TIME)
+        .build()
+        .unwrap();
+    let mut requests = 0;
+    let mut operation = || {
+        requests += 1;
+        async { Err::<(), _>(Error::new(ErrorKind::Operation, "Something went wrong.".to_string())) }
+    };
+    let started_at = Instant::now();
+    assert!(hedge.execute(&mut operation).await.is_err());
+    assert!(started_at.elapsed() >= TIME, "{:?}", started_at.elapsed());
+    assert_eq!(2, requests);
+}
+
+#[rstest]
+#[tokio::test]
+async fn failures_count_toward_circuit_breaker(mut circuit_breaker_async: CircuitBreaker) {
+    let hedge = HedgeBuilder::new()
+        .with_delay(TIME)
+        .with_max_hedges(2)
+        .build()
+        .unwrap();
+    let requests = Cell::new(0);
+    let mut operation = || {
+        requests.set(requests.get() + 1);
+        async {
+            Err::<(), _>(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
+        }
+    };
+    let result = hedge.execute_with_circuit_breaker(&mut circuit_breaker_async, &mut operation).await;
+    assert_eq!(ErrorKind::Operation, result.unwrap_err().kind());
+    assert_eq!(2, requests.get());
+    assert_eq!(State::Open, circuit_breaker_async.state);
+    let result = hedge.execute_with_circuit_breaker(&mut circuit_breaker_async, &mut operation).await;
+    assert_eq!(ErrorKind::CircuitOpen, result.unwrap_err().kind());
+    assert_eq!(2, requests.get());
+}
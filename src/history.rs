@@ -0,0 +1,104 @@
+use crate::circuit_breaker::State;
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone)]
+pub struct Record<E> {
+    attempt: usize,
+    timestamp: SystemTime,
+    delay: Duration,
+    state: State,
+    error: E,
+}
+
+impl<E> Record<E> {
+    pub(crate) fn new(attempt: usize, delay: Duration, state: State, error: E) -> Self {
+        Self {
+            attempt,
+            timestamp: SystemTime::now(),
+            delay,
+            state,
+            error,
+        }
+    }
+
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct History<E> {
+    records: Vec<Record<E>>,
+}
+
+impl<E> History<E> {
+    pub(crate) fn new() -> Self {
+        Self {
+            records: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, record: Record<E>) {
+        self.records.push(record);
+    }
+
+    pub(crate) fn add_delay_to_last(&mut self, delay: Duration) {
+        if let Some(record) = self.records.last_mut() {
+            record.delay += delay;
+        }
+    }
+
+    pub fn records(&self) -> &[Record<E>] {
+        &self.records
+    }
+
+    pub fn last_error(&self) -> Option<&E> {
+        self.records.last().map(Record::error)
+    }
+
+    pub fn into_records(self) -> Vec<Record<E>> {
+        self.records
+    }
+
+    pub fn into_last_error(mut self) -> Option<E> {
+        self.records.pop().map(Record::into_error)
+    }
+}
+
+impl<E: Display> Display for History<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.records.last() {
+            Some(record) => write!(
+                f,
+                "{} attempt(s) failed, last error: {}",
+                self.records.len(),
+                record.error
+            ),
+            None => write!(f, "No attempt recorded"),
+        }
+    }
+}
+
+impl<E: StdError> StdError for History<E> {}
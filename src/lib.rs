@@ -1,14 +1,133 @@
+#![allow(clippy::bool_assert_comparison, clippy::clone_on_copy, clippy::single_match)]
+
+#[cfg(feature = "async")]
+pub mod backend;
+#[cfg(all(test, feature = "async"))]
+#[path = "./backend_test.rs"]
+mod backend_test;
+
 pub mod backoff;
 #[cfg(test)]
 #[path = "./backoff_test.rs"]
 mod backoff_test;
 
+pub mod bulkhead;
+#[cfg(test)]
+#[path = "./bulkhead_test.rs"]
+mod bulkhead_test;
+
 pub mod circuit_breaker;
 #[cfg(test)]
 #[path = "./circuit_breaker_test.rs"]
 mod circuit_breaker_test;
 
+pub mod circuit_breaker_map;
+#[cfg(test)]
+#[path = "./circuit_breaker_map_test.rs"]
+mod circuit_breaker_map_test;
+
+#[cfg(feature = "serde")]
+pub mod config;
+#[cfg(all(test, feature = "serde"))]
+#[path = "./config_test.rs"]
+mod config_test;
+
+pub mod context;
+
+mod dsl;
+#[cfg(test)]
+#[path = "./dsl_test.rs"]
+mod dsl_test;
+
+mod duration;
+
 pub mod error;
 
+pub mod fallback;
+
+#[cfg(feature = "async")]
+pub mod hedge;
+#[cfg(all(test, feature = "async"))]
+#[path = "./hedge_test.rs"]
+mod hedge_test;
+
+pub mod history;
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod macros;
+#[cfg(feature = "macros")]
+pub use whoopsie_macros::{circuit_breaker, retry};
+#[cfg(all(test, feature = "macros"))]
+extern crate self as whoopsie;
+#[cfg(all(test, feature = "macros"))]
+#[path = "./macros_test.rs"]
+mod macros_test;
+
+pub mod policy;
+#[cfg(test)]
+#[path = "./policy_test.rs"]
+mod policy_test;
+
+mod presets;
+#[cfg(test)]
+#[path = "./presets_test.rs"]
+mod presets_test;
+
+pub mod rate_limiter;
+#[cfg(test)]
+#[path = "./rate_limiter_test.rs"]
+mod rate_limiter_test;
+
+pub mod registry;
+#[cfg(test)]
+#[path = "./registry_test.rs"]
+mod registry_test;
+
+pub mod reload;
+#[cfg(all(test, feature = "serde"))]
+#[path = "./reload_test.rs"]
+mod reload_test;
+
+#[cfg(feature = "reqwest-middleware")]
+pub mod reqwest;
+#[cfg(all(test, feature = "reqwest-middleware"))]
+#[path = "./reqwest_test.rs"]
+mod reqwest_test;
+
+pub mod retry_budget;
+#[cfg(test)]
+#[path = "./retry_budget_test.rs"]
+mod retry_budget_test;
+
+pub mod snapshot;
+#[cfg(test)]
+#[path = "./snapshot_test.rs"]
+mod snapshot_test;
+
+pub mod state_store;
+#[cfg(test)]
+#[path = "./state_store_test.rs"]
+mod state_store_test;
+
+mod telemetry;
+#[cfg(all(test, feature = "tracing"))]
+#[path = "./telemetry_test.rs"]
+mod telemetry_test;
+#[cfg(all(test, feature = "metrics"))]
+#[path = "./metrics_test.rs"]
+mod metrics_test;
+
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(all(test, feature = "tower"))]
+#[path = "./tower_test.rs"]
+mod tower_test;
+
+pub mod typestate;
+#[cfg(test)]
+#[path = "./typestate_test.rs"]
+mod typestate_test;
+
 #[cfg(test)]
-pub mod fixtures;
\ No newline at end of file
+pub mod fixtures;
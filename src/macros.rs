@@ -0,0 +1,58 @@
+use crate::circuit_breaker::CircuitBreaker;
+use crate::duration;
+use crate::error::{Error, ErrorKind};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(feature = "async")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_async {
+    () => {};
+}
+
+#[cfg(not(feature = "async"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_async {
+    () => {
+        compile_error!("Retrying or guarding an async function requires the 'async' feature of whoopsie");
+    };
+}
+
+pub fn duration(value: &str) -> Result<Duration, Error> {
+    duration::parse(value).map_err(|description| Error::new(ErrorKind::Configuration, description))
+}
+
+pub fn guard(circuit_breaker: &Mutex<CircuitBreaker>) -> Result<(), Error> {
+    match circuit_breaker.lock().unwrap().rejection() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+pub fn record<O, E>(circuit_breaker: &Mutex<CircuitBreaker>, result: &Result<O, E>) {
+    let mut circuit_breaker = circuit_breaker.lock().unwrap();
+    match result {
+        Ok(_) => circuit_breaker.record_success(),
+        Err(_) => circuit_breaker.record_failure(),
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn guard_async(circuit_breaker: &Mutex<CircuitBreaker>) -> Result<(), Error> {
+    CircuitBreaker::synchronize_shared(circuit_breaker).await;
+    match circuit_breaker.lock().unwrap().current_rejection() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn record_async<O, E>(circuit_breaker: &Mutex<CircuitBreaker>, result: &Result<O, E>) {
+    match result {
+        Ok(_) => circuit_breaker.lock().unwrap().succeed(),
+        Err(_) => circuit_breaker.lock().unwrap().fail(),
+    }
+    CircuitBreaker::synchronize_shared(circuit_breaker).await;
+}
@@ -0,0 +1,141 @@
+use crate::error::{Error, ErrorKind};
+use rstest::rstest;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "async")]
+use std::sync::Arc;
+
+fn failure(description: &str) -> Error {
+    Error::new(ErrorKind::Operation, description.to_string())
+}
+
+static FLAKY_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+#[crate::retry(constant = "10ms", attempts = 3)]
+fn flaky(label: String) -> Result<String, Error> {
+    if FLAKY_CALLS.fetch_add(1, Ordering::SeqCst) < 2 {
+        return Err(failure("Not yet"));
+    }
+    Ok(label)
+}
+
+#[rstest]
+fn retries_synchronous_function() {
+    assert_eq!("ready", flaky("ready".to_string()).unwrap());
+    assert_eq!(3, FLAKY_CALLS.load(Ordering::SeqCst));
+}
+
+static EXHAUSTED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+#[crate::retry(exp = "1ms", factor = 1.5, max = "5ms", jitter = "full", attempts = 4)]
+fn exhausted(attempt: &AtomicUsize) -> Result<(), Error> {
+    attempt.fetch_add(1, Ordering::SeqCst);
+    Err(failure(&format!("Failed {} time(s)", attempt.load(Ordering::SeqCst))))
+}
+
+#[rstest]
+fn returns_last_error_when_exhausted() {
+    let error = exhausted(&EXHAUSTED_CALLS).unwrap_err();
+    assert_eq!("Failed 4 time(s)", error.description);
+}
+
+struct Client {
+    calls: AtomicUsize,
+}
+
+impl Client {
+    #[crate::retry(preset = "aggressive", jitter = 0.2)]
+    fn fetch(&self, path: &str) -> Result<String, Error> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(failure("Connection reset"));
+        }
+        Ok(path.to_uppercase())
+    }
+}
+
+#[rstest]
+fn retries_methods() {
+    let client = Client {
+        calls: AtomicUsize::new(0),
+    };
+    assert_eq!("/HEALTH", client.fetch("/health").unwrap());
+    assert_eq!(2, client.calls.load(Ordering::SeqCst));
+}
+
+#[cfg(feature = "async")]
+#[crate::retry(constant = "10ms", attempts = 3)]
+async fn flaky_async(calls: Arc<AtomicUsize>, label: String) -> Result<String, Error> {
+    tokio::task::yield_now().await;
+    if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+        return Err(failure("Not yet"));
+    }
+    Ok(label)
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn retries_asynchronous_function() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    assert_eq!("ready", flaky_async(calls.clone(), "ready".to_string()).await.unwrap());
+    assert_eq!(3, calls.load(Ordering::SeqCst));
+}
+
+#[crate::circuit_breaker(name = "db", failure_threshold = 2, reset_timeout = "1h")]
+fn query(succeed: bool) -> Result<&'static str, Error> {
+    match succeed {
+        true => Ok("rows"),
+        false => Err(failure("Connection refused")),
+    }
+}
+
+#[rstest]
+fn circuit_breaker_opens_after_failures() {
+    assert_eq!("rows", query(true).unwrap());
+    assert!(query(false).is_err());
+    assert!(query(false).is_err());
+    let error = query(true).unwrap_err();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    assert_eq!("Circuit breaker 'db' is open", error.description);
+}
+
+#[cfg(feature = "async")]
+#[crate::circuit_breaker(preset = "aggressive", reset_timeout = "1h")]
+async fn query_async(succeed: bool) -> Result<&'static str, Error> {
+    tokio::task::yield_now().await;
+    match succeed {
+        true => Ok("rows"),
+        false => Err(failure("Connection refused")),
+    }
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn circuit_breaker_guards_asynchronous_function() {
+    assert_eq!("rows", query_async(true).await.unwrap());
+    assert!(query_async(false).await.is_err());
+    assert!(query_async(false).await.is_err());
+    assert_eq!(ErrorKind::CircuitOpen, query_async(true).await.unwrap_err().kind());
+}
+
+#[crate::retry(constant = "10 fortnights")]
+fn misconfigured() -> Result<(), Error> {
+    Ok(())
+}
+
+#[rstest]
+#[should_panic(expected = "Invalid retry configuration: Invalid duration unit 'fortnights' in '10 fortnights'")]
+fn reports_invalid_duration_on_first_call() {
+    let _ = misconfigured();
+}
+
+#[crate::circuit_breaker(preset = "reckless")]
+fn unknown_preset() -> Result<(), Error> {
+    Ok(())
+}
+
+#[rstest]
+#[should_panic(expected = "Invalid circuit breaker configuration: Unknown preset 'reckless'")]
+fn reports_unknown_preset_on_first_call() {
+    let _ = unknown_preset();
+}
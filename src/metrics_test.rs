@@ -0,0 +1,81 @@
+use crate::backoff::WaitStrategy;
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::Error;
+use crate::fixtures::{backoff_builder, circuit_breaker_builder, failed_operation, failed_operation_then_recovered_at_first_attempt, TIME};
+use metrics::{SharedString, Unit};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use metrics_util::{CompositeKey, MetricKind};
+use rstest::rstest;
+
+type Snapshot = Vec<(CompositeKey, Option<Unit>, Option<SharedString>, DebugValue)>;
+
+fn value<'a>(snapshot: &'a Snapshot, kind: MetricKind, name: &str) -> Option<&'a DebugValue> {
+    snapshot.iter()
+        .find(|(key, _, _, _)| {
+            key.kind() == kind
+                && key.key().name() == name
+                && key.key().labels().any(|label| label.key() == "name" && label.value() == "payments-api")
+        })
+        .map(|(_, _, _, value)| value)
+}
+
+#[rstest]
+fn records_calls_retries_and_latency(
+    #[with(1, 2, backoff_builder(TIME / 10, 3, WaitStrategy::Synchronous))] mut circuit_breaker_builder: CircuitBreakerBuilder,
+    mut failed_operation_then_recovered_at_first_attempt: impl FnMut() -> Result<(), Error>
+) {
+    let mut circuit_breaker = circuit_breaker_builder.with_name("payments-api").build().unwrap();
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        assert!(circuit_breaker.retry(&mut failed_operation_then_recovered_at_first_attempt).is_ok());
+    });
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(Some(&DebugValue::Counter(1)), value(&snapshot, MetricKind::Counter, "whoopsie_calls_total"));
+    assert_eq!(Some(&DebugValue::Counter(2)), value(&snapshot, MetricKind::Counter, "whoopsie_failures_total"));
+    assert_eq!(Some(&DebugValue::Counter(2)), value(&snapshot, MetricKind::Counter, "whoopsie_retries_total"));
+    assert_eq!(Some(&DebugValue::Counter(1)), value(&snapshot, MetricKind::Counter, "whoopsie_successes_total"));
+    match value(&snapshot, MetricKind::Histogram, "whoopsie_backoff_delay_seconds") {
+        Some(DebugValue::Histogram(values)) => assert_eq!(2, values.len()),
+        other => panic!("unexpected backoff delay {:?}", other),
+    }
+    match value(&snapshot, MetricKind::Histogram, "whoopsie_call_duration_seconds") {
+        Some(DebugValue::Histogram(values)) => assert_eq!(1, values.len()),
+        other => panic!("unexpected call duration {:?}", other),
+    }
+}
+
+#[rstest]
+fn records_rejections_and_state(
+    #[with(1, 2, backoff_builder(TIME / 10, 3, WaitStrategy::Synchronous))] mut circuit_breaker_builder: CircuitBreakerBuilder,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let mut circuit_breaker = circuit_breaker_builder.with_name("payments-api").build().unwrap();
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        circuit_breaker.record_failure();
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.retry(&mut failed_operation).is_err());
+    });
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(Some(&DebugValue::Counter(1)), value(&snapshot, MetricKind::Counter, "whoopsie_rejections_total"));
+    assert_eq!(
+        Some(&DebugValue::Gauge(1.0.into())),
+        value(&snapshot, MetricKind::Gauge, "whoopsie_circuit_breaker_state")
+    );
+}
+
+#[rstest]
+fn records_initial_state(mut circuit_breaker_builder: CircuitBreakerBuilder) {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        circuit_breaker_builder.with_name("payments-api").build().unwrap();
+    });
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(
+        Some(&DebugValue::Gauge(0.0.into())),
+        value(&snapshot, MetricKind::Gauge, "whoopsie_circuit_breaker_state")
+    );
+}
@@ -0,0 +1,379 @@
+use crate::backoff::Backoff;
+use crate::bulkhead::Bulkhead;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::error::Error;
+#[cfg(feature = "async")]
+use crate::error::ErrorKind;
+use crate::rate_limiter::RateLimiter;
+use crate::telemetry;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+pub trait Policy<O, E> {
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E>;
+}
+
+pub struct Next<'a, O, E> {
+    policies: &'a mut [Box<dyn Policy<O, E>>],
+    operation: &'a mut dyn FnMut() -> Result<O, E>,
+}
+
+impl<O, E> Next<'_, O, E> {
+    pub fn run(&mut self) -> Result<O, E> {
+        match self.policies.split_first_mut() {
+            Some((policy, policies)) => policy.execute(&mut Next {
+                policies,
+                operation: &mut *self.operation,
+            }),
+            None => (self.operation)(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+pub trait AsyncPolicy<O, E>: Send {
+    fn execute_async<'a>(&'a mut self, next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>>;
+}
+
+#[cfg(feature = "async")]
+pub struct NextAsync<'a, O, E> {
+    policies: &'a mut [Box<dyn AsyncPolicy<O, E>>],
+    operation: &'a mut (dyn FnMut() -> BoxFuture<'static, Result<O, E>> + Send),
+}
+
+#[cfg(feature = "async")]
+impl<O, E> NextAsync<'_, O, E> {
+    pub fn run(&mut self) -> BoxFuture<'_, Result<O, E>> {
+        match self.policies.split_first_mut() {
+            Some((policy, policies)) => policy.execute_async(NextAsync {
+                policies,
+                operation: &mut *self.operation,
+            }),
+            None => (self.operation)(),
+        }
+    }
+}
+
+pub struct PipelineBuilder<O, E> {
+    policies: Vec<Box<dyn Policy<O, E>>>,
+}
+
+impl<O, E> Default for PipelineBuilder<O, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<O, E> PipelineBuilder<O, E> {
+    pub fn new() -> Self {
+        Self {
+            policies: Vec::new(),
+        }
+    }
+
+    pub fn with_policy<P>(&mut self, policy: P) -> &mut Self
+    where
+        P: Policy<O, E> + 'static,
+    {
+        self.policies.push(Box::new(policy));
+        self
+    }
+
+    pub fn build(&mut self) -> Pipeline<O, E> {
+        Pipeline {
+            policies: std::mem::take(&mut self.policies),
+        }
+    }
+}
+
+pub struct Pipeline<O, E> {
+    policies: Vec<Box<dyn Policy<O, E>>>,
+}
+
+impl<O, E> Pipeline<O, E> {
+    pub fn execute<F>(&mut self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut() -> Result<O, E>,
+    {
+        Next {
+            policies: &mut self.policies,
+            operation,
+        }.run()
+    }
+}
+
+#[cfg(feature = "async")]
+pub struct AsyncPipelineBuilder<O, E> {
+    policies: Vec<Box<dyn AsyncPolicy<O, E>>>,
+}
+
+#[cfg(feature = "async")]
+impl<O, E> Default for AsyncPipelineBuilder<O, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPipelineBuilder<O, E> {
+    pub fn new() -> Self {
+        Self {
+            policies: Vec::new(),
+        }
+    }
+
+    pub fn with_policy<P>(&mut self, policy: P) -> &mut Self
+    where
+        P: AsyncPolicy<O, E> + 'static,
+    {
+        self.policies.push(Box::new(policy));
+        self
+    }
+
+    pub fn build(&mut self) -> AsyncPipeline<O, E> {
+        AsyncPipeline {
+            policies: std::mem::take(&mut self.policies),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+pub struct AsyncPipeline<O, E> {
+    policies: Vec<Box<dyn AsyncPolicy<O, E>>>,
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPipeline<O, E> {
+    pub async fn execute_async<F, R>(&mut self, operation: &mut F) -> Result<O, E>
+    where
+        F: FnMut() -> R + Send,
+        R: Future<Output = Result<O, E>> + Send + 'static,
+    {
+        let mut operation = || -> BoxFuture<'static, Result<O, E>> { Box::pin(operation()) };
+        NextAsync {
+            policies: &mut self.policies,
+            operation: &mut operation,
+        }.run().await
+    }
+}
+
+#[cfg(feature = "async")]
+pub struct Timeout {
+    duration: Duration,
+}
+
+#[cfg(feature = "async")]
+impl Timeout {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+        }
+    }
+
+    fn error(&self) -> Error {
+        Error::new(ErrorKind::Timeout, format!("Operation timed out after {:?}", self.duration))
+    }
+}
+
+pub struct Fallback<F> {
+    fallback: F,
+}
+
+impl<F> Fallback<F> {
+    pub fn new(fallback: F) -> Self {
+        Self {
+            fallback,
+        }
+    }
+}
+
+impl<O, E> Policy<O, E> for CircuitBreaker
+where
+    E: From<Error>,
+{
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E> {
+        let _span = telemetry::circuit_breaker_span(self.name(), self.state()).entered();
+        let started_at = Instant::now();
+        telemetry::call_started(self.name());
+        let result = match self.rejection() {
+            Some(error) => {
+                telemetry::rejected(self.name(), &error);
+                Err(error.into())
+            }
+            None => {
+                telemetry::attempt_started(self.name(), 1);
+                let result = next.run();
+                match result {
+                    Ok(_) => {
+                        telemetry::attempt_succeeded(self.name(), 1);
+                        self.record_success();
+                    }
+                    Err(_) => {
+                        telemetry::attempt_classified_failed(self.name(), 1, Duration::ZERO);
+                        self.record_failure();
+                    }
+                }
+                result
+            }
+        };
+        telemetry::call_finished(self.name(), started_at.elapsed());
+        result
+    }
+}
+
+impl<O, E> Policy<O, E> for Backoff
+where
+    E: std::error::Error + From<Error>,
+{
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E> {
+        self.ensure_synchronous()?;
+        self.reset();
+        self.retry(&mut || next.run())
+    }
+}
+
+impl<O, E> Policy<O, E> for Bulkhead
+where
+    E: From<Error>,
+{
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E> {
+        let _permit = self.acquire()?;
+        next.run()
+    }
+}
+
+impl<O, E> Policy<O, E> for RateLimiter
+where
+    E: From<Error>,
+{
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E> {
+        self.acquire()?;
+        next.run()
+    }
+}
+
+impl<O, E, F> Policy<O, E> for Fallback<F>
+where
+    F: FnMut(E) -> O,
+{
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E> {
+        next.run().or_else(|error| Ok((self.fallback)(error)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPolicy<O, E> for CircuitBreaker
+where
+    O: Send + 'static,
+    E: From<Error> + Send + 'static,
+{
+    fn execute_async<'a>(&'a mut self, mut next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>> {
+        Box::pin(async move {
+            let span = telemetry::circuit_breaker_span(self.name(), self.state());
+            let started_at = Instant::now();
+            telemetry::call_started(self.name());
+            let result = telemetry::instrument(span, async {
+                self.synchronize_async().await;
+                if let Some(error) = self.current_rejection() {
+                    telemetry::rejected(self.name(), &error);
+                    return Err(error.into());
+                }
+                telemetry::attempt_started(self.name(), 1);
+                let result = next.run().await;
+                match result {
+                    Ok(_) => {
+                        telemetry::attempt_succeeded(self.name(), 1);
+                        self.succeed();
+                    }
+                    Err(_) => {
+                        telemetry::attempt_classified_failed(self.name(), 1, Duration::ZERO);
+                        self.fail();
+                    }
+                }
+                self.synchronize_async().await;
+                result
+            }).await;
+            telemetry::call_finished(self.name(), started_at.elapsed());
+            result
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPolicy<O, E> for Backoff
+where
+    O: Send + 'static,
+    E: From<Error> + Send + 'static,
+{
+    fn execute_async<'a>(&'a mut self, mut next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>> {
+        Box::pin(async move {
+            self.ensure_asynchronous()?;
+            self.reset();
+            self.retry_next_async(&mut next).await
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPolicy<O, E> for Bulkhead
+where
+    O: Send + 'static,
+    E: From<Error> + Send + 'static,
+{
+    fn execute_async<'a>(&'a mut self, mut next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>> {
+        Box::pin(async move {
+            let _permit = self.acquire_async().await?;
+            next.run().await
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPolicy<O, E> for RateLimiter
+where
+    O: Send + 'static,
+    E: From<Error> + Send + 'static,
+{
+    fn execute_async<'a>(&'a mut self, mut next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>> {
+        Box::pin(async move {
+            self.acquire_async().await?;
+            next.run().await
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E> AsyncPolicy<O, E> for Timeout
+where
+    O: Send + 'static,
+    E: From<Error> + Send + 'static,
+{
+    fn execute_async<'a>(&'a mut self, mut next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>> {
+        Box::pin(async move {
+            match tokio::time::timeout(self.duration, next.run()).await {
+                Ok(result) => result,
+                Err(_) => Err(self.error().into()),
+            }
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O, E, F> AsyncPolicy<O, E> for Fallback<F>
+where
+    O: Send + 'static,
+    E: Send + 'static,
+    F: FnMut(E) -> O + Send,
+{
+    fn execute_async<'a>(&'a mut self, mut next: NextAsync<'a, O, E>) -> BoxFuture<'a, Result<O, E>> {
+        Box::pin(async move {
+            next.run().await.or_else(|error| Ok((self.fallback)(error)))
+        })
+    }
+}
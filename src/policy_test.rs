@@ -0,0 +1,145 @@
+use crate::backoff::BackoffBuilder;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{circuit_breaker, failed_operation, failed_operation_then_recovered_at_first_attempt, TIME};
+#[cfg(feature = "async")]
+use crate::policy::AsyncPipelineBuilder;
+#[cfg(feature = "async")]
+use crate::policy::Timeout;
+use crate::policy::{Fallback, Next, PipelineBuilder, Policy};
+use rstest::rstest;
+use std::cell::RefCell;
+use std::rc::Rc;
+#[cfg(feature = "async")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "async")]
+use std::sync::Arc;
+
+struct Trace {
+    name: &'static str,
+    calls: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl<O, E> Policy<O, E> for Trace {
+    fn execute(&mut self, next: &mut Next<'_, O, E>) -> Result<O, E> {
+        self.calls.borrow_mut().push(self.name);
+        next.run()
+    }
+}
+
+#[rstest]
+fn policies_run_in_order() {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let mut pipeline = PipelineBuilder::<(), Error>::new()
+        .with_policy(Trace { name: "outer", calls: calls.clone() })
+        .with_policy(Trace { name: "inner", calls: calls.clone() })
+        .build();
+    let operation_calls = calls.clone();
+    let result = pipeline.execute(&mut || {
+        operation_calls.borrow_mut().push("operation");
+        Ok(())
+    });
+    assert!(result.is_ok());
+    assert_eq!(vec!["outer", "inner", "operation"], *calls.borrow());
+}
+
+#[rstest]
+fn retry_inside_circuit_breaker(
+    circuit_breaker: CircuitBreaker,
+    mut failed_operation_then_recovered_at_first_attempt: impl FnMut() -> Result<(), Error>
+) {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let mut pipeline = PipelineBuilder::new()
+        .with_policy(circuit_breaker)
+        .with_policy(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .with_attempts(3)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .with_policy(Trace { name: "attempt", calls: calls.clone() })
+        .build();
+    let result = pipeline.execute(&mut failed_operation_then_recovered_at_first_attempt);
+    assert!(result.is_ok());
+    assert_eq!(3, calls.borrow().len());
+}
+
+#[rstest]
+fn fallback_when_circuit_breaker_opens(
+    circuit_breaker: CircuitBreaker,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let mut pipeline = PipelineBuilder::new()
+        .with_policy(Fallback::new(|error: Error| error.kind()))
+        .with_policy(circuit_breaker)
+        .build();
+    let mut calls = 0;
+    let mut operation = || {
+        calls += 1;
+        failed_operation().map(|_| ErrorKind::Operation)
+    };
+    assert_eq!(ErrorKind::Operation, pipeline.execute(&mut operation).unwrap());
+    assert_eq!(ErrorKind::Operation, pipeline.execute(&mut operation).unwrap());
+    assert_eq!(ErrorKind::CircuitOpen, pipeline.execute(&mut operation).unwrap());
+    assert_eq!(2, calls);
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+fn asynchronous_backoff_in_synchronous_pipeline(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut pipeline = PipelineBuilder::new()
+        .with_policy(BackoffBuilder::new()
+            .with_constant_time(TIME)
+            .as_asynchronous()
+            .build()
+            .unwrap()
+        )
+        .build();
+    assert_eq!(ErrorKind::Configuration, pipeline.execute(&mut failed_operation).unwrap_err().kind());
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn synchronous_backoff_in_asynchronous_pipeline() {
+    let mut pipeline = AsyncPipelineBuilder::new()
+        .with_policy(BackoffBuilder::new()
+            .with_constant_time(TIME)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .build();
+    let result = pipeline.execute_async(&mut || async { Ok::<_, Error>(()) }).await;
+    assert_eq!(ErrorKind::Configuration, result.unwrap_err().kind());
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn timeout_then_retry_as_async() {
+    let mut pipeline = AsyncPipelineBuilder::new()
+        .with_policy(Fallback::new(|error: Error| error.description))
+        .with_policy(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .with_attempts(2)
+            .as_asynchronous()
+            .build()
+            .unwrap()
+        )
+        .with_policy(Timeout::new(TIME))
+        .build();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut operation = || {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if call == 0 {
+                tokio::time::sleep(TIME * 10).await;
+            }
+            Ok::<_, Error>("done".to_string())
+        }
+    };
+    assert_eq!("done", pipeline.execute_async(&mut operation).await.unwrap());
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+}
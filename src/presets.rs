@@ -0,0 +1,95 @@
+use crate::backoff::{BackoffBuilder, Jitter};
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::{Error, ErrorKind};
+use crate::retry_budget::RetryBudgetBuilder;
+use std::time::Duration;
+
+const BACKOFF_PRESETS: [&str; 5] = ["aggressive", "standard", "patient", "aws_standard", "grpc"];
+const CIRCUIT_BREAKER_PRESETS: [&str; 3] = ["aggressive", "standard", "patient"];
+
+fn unknown(name: &str, presets: &[&str]) -> Error {
+    Error::new(ErrorKind::Configuration, format!("Unknown preset '{}', expected one of {}", name, presets.join(", ")))
+}
+
+fn exponential(duration: Duration, factor: f32, max_time: Duration, jitter: Jitter, attempts: usize) -> BackoffBuilder {
+    let mut builder = BackoffBuilder::new();
+    builder
+        .with_exponential_time(duration, factor)
+        .with_max_time(max_time)
+        .with_jitter(jitter)
+        .with_attempts(attempts)
+        .as_synchronous();
+    builder
+}
+
+impl BackoffBuilder {
+    pub fn preset(name: &str) -> Result<Self, Error> {
+        match name {
+            "aggressive" => Ok(Self::aggressive()),
+            "standard" => Ok(Self::standard()),
+            "patient" => Ok(Self::patient()),
+            "aws_standard" => Ok(Self::aws_standard()),
+            "grpc" => Ok(Self::grpc()),
+            _ => Err(unknown(name, &BACKOFF_PRESETS)),
+        }
+    }
+
+    pub fn aggressive() -> Self {
+        exponential(Duration::from_millis(50), 2.0, Duration::from_secs(1), Jitter::Full, 3)
+    }
+
+    pub fn standard() -> Self {
+        exponential(Duration::from_millis(100), 2.0, Duration::from_secs(10), Jitter::Full, 5)
+    }
+
+    pub fn patient() -> Self {
+        exponential(Duration::from_secs(1), 2.0, Duration::from_secs(60), Jitter::Equal, 10)
+    }
+
+    pub fn aws_standard() -> Self {
+        let mut builder = exponential(Duration::from_millis(100), 2.0, Duration::from_secs(20), Jitter::Full, 3);
+        let retry_budget = RetryBudgetBuilder::new()
+            .with_token_bucket(500, 5, 10)
+            .build()
+            .expect("Preset retry budget is valid");
+        builder.with_retry_budget(retry_budget);
+        builder
+    }
+
+    pub fn grpc() -> Self {
+        exponential(Duration::from_secs(1), 1.6, Duration::from_secs(120), Jitter::Proportional(0.2), 5)
+    }
+}
+
+fn circuit_breaker(attempts: usize, threshold: usize, reset_timeout: Duration, backoff: BackoffBuilder) -> CircuitBreakerBuilder {
+    let mut builder = CircuitBreakerBuilder::new();
+    builder
+        .with_attempts(attempts)
+        .with_failure_threshold(threshold)
+        .with_reset_timeout(reset_timeout)
+        .with_backoff_builder(backoff);
+    builder
+}
+
+impl CircuitBreakerBuilder {
+    pub fn preset(name: &str) -> Result<Self, Error> {
+        match name {
+            "aggressive" => Ok(Self::aggressive()),
+            "standard" => Ok(Self::standard()),
+            "patient" => Ok(Self::patient()),
+            _ => Err(unknown(name, &CIRCUIT_BREAKER_PRESETS)),
+        }
+    }
+
+    pub fn aggressive() -> Self {
+        circuit_breaker(1, 2, Duration::from_secs(5), BackoffBuilder::aggressive())
+    }
+
+    pub fn standard() -> Self {
+        circuit_breaker(2, 5, Duration::from_secs(30), BackoffBuilder::standard())
+    }
+
+    pub fn patient() -> Self {
+        circuit_breaker(3, 10, Duration::from_secs(60), BackoffBuilder::patient())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::backoff::{BackoffBuilder, Jitter};
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::Error;
+use crate::fixtures::{failed_operation, TIME};
+use rstest::rstest;
+use std::time::Instant;
+
+#[rstest]
+#[case("aggressive", "exp(50ms, x2, max=1s, jitter=full, attempts=3)")]
+#[case("standard", "exp(100ms, x2, max=10s, jitter=full, attempts=5)")]
+#[case("patient", "exp(1s, x2, max=1m, jitter=equal, attempts=10)")]
+#[case("aws_standard", "exp(100ms, x2, max=20s, jitter=full, attempts=3, budget=tokens:500:5:10)")]
+#[case("grpc", "exp(1s, x1.6, max=2m, jitter=0.2, attempts=5)")]
+fn backoff_presets(#[case] name: &str, #[case] expected: &str) {
+    let backoff = BackoffBuilder::preset(name).unwrap().build().unwrap();
+    assert_eq!(expected, backoff.to_string());
+}
+
+#[rstest]
+#[case("aggressive", "Attempts: 0/1, Failures: 0/2, State: Closed")]
+#[case("standard", "Attempts: 0/2, Failures: 0/5, State: Closed")]
+#[case("patient", "Attempts: 0/3, Failures: 0/10, State: Closed")]
+fn circuit_breaker_presets(#[case] name: &str, #[case] expected: &str) {
+    let circuit_breaker = CircuitBreakerBuilder::preset(name).unwrap().build().unwrap();
+    assert_eq!(expected, circuit_breaker.to_string());
+}
+
+#[rstest]
+fn unknown_preset() {
+    let error = BackoffBuilder::preset("reckless").err().unwrap();
+    assert_eq!("Unknown preset 'reckless', expected one of aggressive, standard, patient, aws_standard, grpc", error.description);
+    let error = CircuitBreakerBuilder::preset("grpc").err().unwrap();
+    assert_eq!("Unknown preset 'grpc', expected one of aggressive, standard, patient", error.description);
+}
+
+#[rstest]
+fn presets_can_be_tweaked() {
+    let backoff = BackoffBuilder::standard()
+        .with_attempts(7)
+        .with_jitter(Jitter::None)
+        .as_spin_loop()
+        .build()
+        .unwrap();
+    assert_eq!("exp(100ms, x2, max=10s, attempts=7, wait=spin)", backoff.to_string());
+    let circuit_breaker = CircuitBreakerBuilder::standard()
+        .with_name("db")
+        .with_failure_threshold(3)
+        .build()
+        .unwrap();
+    assert_eq!("Name: db, Attempts: 0/2, Failures: 0/3, State: Closed", circuit_breaker.to_string());
+}
+
+#[rstest]
+fn aws_standard_limits_retries_with_token_bucket() {
+    let backoff = BackoffBuilder::aws_standard().build().unwrap();
+    let retry_budget = backoff.retry_budget.unwrap();
+    assert_eq!(500, retry_budget.balance());
+    assert!(retry_budget.try_withdraw_timeout());
+    assert_eq!(490, retry_budget.balance());
+    while retry_budget.try_withdraw() {}
+    assert_eq!(0, retry_budget.balance());
+    retry_budget.refund(true);
+    assert_eq!(5, retry_budget.balance());
+}
+
+#[rstest]
+fn circuit_breaker_presets_keep_backoff_builder() {
+    let error = CircuitBreakerBuilder::standard()
+        .with_backoff_builder(BackoffBuilder::new())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!("Time strategy is required. Call one of 'with_*' method", error.description);
+    let circuit_breaker = CircuitBreakerBuilder::standard()
+        .with_backoff_builder(BackoffBuilder::standard().as_spin_loop().clone())
+        .build()
+        .unwrap();
+    assert_eq!("Attempts: 0/2, Failures: 0/5, State: Closed", circuit_breaker.to_string());
+}
+
+#[rstest]
+fn proportional_jitter_stays_near_delay(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME)
+        .with_jitter(Jitter::Proportional(0.2))
+        .with_attempts(3)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let started_at = Instant::now();
+    assert!(backoff.retry(&mut failed_operation).is_err());
+    let elapsed = started_at.elapsed();
+    assert!(elapsed >= TIME * 3 * 8 / 10, "{:?}", elapsed);
+    assert!(elapsed < TIME * 3 * 14 / 10, "{:?}", elapsed);
+}
+
+#[rstest]
+fn rejects_invalid_jitter_ratio() {
+    let error = BackoffBuilder::grpc().with_jitter(Jitter::Proportional(1.5)).build().err().unwrap();
+    assert_eq!("Jitter ratio must be between 0.0 and 1.0", error.description);
+}
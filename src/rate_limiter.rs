@@ -0,0 +1,210 @@
+use crate::backoff::WaitStrategy;
+use crate::error::{Error, ErrorKind};
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    tokens_per_second: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.tokens_per_second).min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    fn next_delay(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((1.0 - self.tokens) / self.tokens_per_second)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimiterBuilder {
+    permits: Option<usize>,
+    period: Option<Duration>,
+    capacity: Option<usize>,
+    max_wait: Option<Duration>,
+    wait_strategy: Option<WaitStrategy>,
+}
+
+impl Default for RateLimiterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiterBuilder {
+    pub fn new() -> Self {
+        Self {
+            permits: None,
+            period: None,
+            capacity: None,
+            max_wait: None,
+            wait_strategy: None,
+        }
+    }
+
+    pub fn with_rate(&mut self, permits: usize, period: Duration) -> &mut Self {
+        self.permits = Some(permits);
+        self.period = Some(period);
+        self
+    }
+
+    pub fn with_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_max_wait(&mut self, duration: Duration) -> &mut Self {
+        self.max_wait = Some(duration);
+        self
+    }
+
+    pub fn as_synchronous(&mut self) -> &mut Self {
+        self.wait_strategy = Some(WaitStrategy::Synchronous);
+        self
+    }
+
+    #[cfg(feature = "async")]
+    pub fn as_asynchronous(&mut self) -> &mut Self {
+        self.wait_strategy = Some(WaitStrategy::Asynchronous);
+        self
+    }
+
+    pub fn as_spin_loop(&mut self) -> &mut Self {
+        self.wait_strategy = Some(WaitStrategy::SpinLoop);
+        self
+    }
+
+    pub fn build(&self) -> Result<RateLimiter, Error> {
+        let (permits, period) = match (self.permits, self.period) {
+            (Some(permits), Some(period)) => (permits, period),
+            _ => {
+                return Err(Error::new(ErrorKind::Configuration, "Rate is required. Call 'with_rate' method".to_string()))
+            }
+        };
+        if permits == 0 || period.is_zero() {
+            return Err(Error::new(ErrorKind::Configuration, "Rate must allow at least one permit per non-zero period".to_string()))
+        }
+        if self.wait_strategy.is_none() {
+            return Err(Error::new(ErrorKind::Configuration, "Wait strategy is required. Call one of 'as_*' method".to_string()))
+        }
+        if self.capacity == Some(0) {
+            return Err(Error::new(ErrorKind::Configuration, "Capacity must be greater than zero".to_string()))
+        }
+        let capacity = self.capacity.unwrap_or(permits) as f64;
+        Ok(RateLimiter {
+            bucket: Arc::new(Mutex::new(Bucket {
+                capacity,
+                tokens: capacity,
+                tokens_per_second: permits as f64 / period.as_secs_f64(),
+                refilled_at: Instant::now(),
+            })),
+            max_wait: self.max_wait,
+            wait_strategy: self.wait_strategy.as_ref().unwrap().clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+    max_wait: Option<Duration>,
+    wait_strategy: WaitStrategy,
+}
+
+impl RateLimiter {
+    pub fn available_permits(&self) -> usize {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(Instant::now());
+        bucket.tokens.max(0.0) as usize
+    }
+
+    pub fn try_acquire(&self) -> Result<(), Error> {
+        self.reserve(Some(Duration::ZERO)).map(|_| ())
+    }
+
+    pub fn acquire(&self) -> Result<(), Error> {
+        self.ensure_synchronous()?;
+        let delay = self.reserve(self.max_wait)?;
+        if !delay.is_zero() {
+            self.wait_strategy.wait(&delay);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn acquire_async(&self) -> Result<(), Error> {
+        self.ensure_asynchronous()?;
+        let delay = self.reserve(self.max_wait)?;
+        if !delay.is_zero() {
+            self.wait_strategy.wait_async(&delay).await;
+        }
+        Ok(())
+    }
+
+    pub fn execute<F, O, E>(&self, operation: F) -> Result<O, E>
+    where
+        F: FnOnce() -> Result<O, E>,
+        E: From<Error>,
+    {
+        self.acquire()?;
+        operation()
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn execute_async<F, O, E, R>(&self, operation: F) -> Result<O, E>
+    where
+        F: FnOnce() -> R,
+        E: From<Error>,
+        R: Future<Output = Result<O, E>>,
+    {
+        self.acquire_async().await?;
+        operation().await
+    }
+
+    fn ensure_synchronous(&self) -> Result<(), Error> {
+        match self.wait_strategy {
+            #[cfg(feature = "async")]
+            WaitStrategy::Asynchronous => Err(Error::new(
+                ErrorKind::Configuration,
+                "Rate limiter built 'as_asynchronous' cannot wait in a synchronous context".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn ensure_asynchronous(&self) -> Result<(), Error> {
+        match self.wait_strategy {
+            WaitStrategy::Asynchronous => Ok(()),
+            _ => Err(Error::new(
+                ErrorKind::Configuration,
+                "Rate limiter must be built 'as_asynchronous' to wait in an asynchronous context".to_string(),
+            )),
+        }
+    }
+
+    fn reserve(&self, max_wait: Option<Duration>) -> Result<Duration, Error> {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(Instant::now());
+        let delay = bucket.next_delay();
+        if let Some(max_wait) = max_wait {
+            if delay > max_wait {
+                return Err(Error::new(ErrorKind::RateLimited, "Rate limit exceeded".to_string()));
+            }
+        }
+        bucket.tokens -= 1.0;
+        Ok(delay)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::backoff::BackoffBuilder;
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{success_operation, TIME};
+use crate::rate_limiter::RateLimiterBuilder;
+use rstest::rstest;
+use std::time::Instant;
+
+#[rstest]
+fn missing_with_rate_method_in_builder() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .as_synchronous()
+        .build();
+    assert_eq!("Rate is required. Call 'with_rate' method", rate_limiter.unwrap_err().description);
+}
+
+#[rstest]
+fn missing_with_as_method_in_builder() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME)
+        .build();
+    assert_eq!("Wait strategy is required. Call one of 'as_*' method", rate_limiter.unwrap_err().description);
+}
+
+#[rstest]
+fn zero_capacity_in_builder() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME)
+        .with_capacity(0)
+        .as_synchronous()
+        .build();
+    assert_eq!("Capacity must be greater than zero", rate_limiter.unwrap_err().description);
+}
+
+#[rstest]
+fn try_acquire_until_empty() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(2, TIME * 10)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    assert_eq!(2, rate_limiter.available_permits());
+    assert!(rate_limiter.try_acquire().is_ok());
+    assert!(rate_limiter.try_acquire().is_ok());
+    assert_eq!(ErrorKind::RateLimited, rate_limiter.try_acquire().unwrap_err().kind());
+}
+
+#[rstest]
+fn acquire_waits_for_refill() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME)
+        .as_spin_loop()
+        .build()
+        .unwrap();
+    let started_at = Instant::now();
+    rate_limiter.acquire().unwrap();
+    rate_limiter.acquire().unwrap();
+    rate_limiter.acquire().unwrap();
+    assert!(started_at.elapsed() >= TIME * 19 / 10);
+}
+
+#[rstest]
+fn acquire_rejects_beyond_max_wait() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME * 10)
+        .with_max_wait(TIME)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    rate_limiter.acquire().unwrap();
+    assert_eq!(ErrorKind::RateLimited, rate_limiter.acquire().unwrap_err().kind());
+}
+
+#[rstest]
+fn in_front_of_circuit_breaker(mut success_operation: impl FnMut() -> Result<(), Error>) {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME * 10)
+        .with_max_wait(TIME)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let mut circuit_breaker = CircuitBreakerBuilder::new()
+        .with_reset_timeout(TIME)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .build()
+        .unwrap();
+    assert!(rate_limiter.execute(|| circuit_breaker.retry(&mut success_operation)).is_ok());
+    let error = rate_limiter.execute(|| circuit_breaker.retry(&mut success_operation)).unwrap_err();
+    assert_eq!(ErrorKind::RateLimited, error.kind());
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn acquire_waits_for_refill_as_async() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME)
+        .as_asynchronous()
+        .build()
+        .unwrap();
+    let started_at = Instant::now();
+    rate_limiter.acquire_async().await.unwrap();
+    rate_limiter.execute_async(|| async { Ok::<_, Error>(()) }).await.unwrap();
+    assert!(started_at.elapsed() >= TIME * 9 / 10);
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+fn acquire_rejects_asynchronous_wait_strategy() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME)
+        .as_asynchronous()
+        .build()
+        .unwrap();
+    let error = rate_limiter.acquire().unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!("Rate limiter built 'as_asynchronous' cannot wait in a synchronous context", error.description);
+    assert_eq!(1, rate_limiter.available_permits());
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn acquire_async_rejects_synchronous_wait_strategy() {
+    let rate_limiter = RateLimiterBuilder::new()
+        .with_rate(1, TIME)
+        .as_spin_loop()
+        .build()
+        .unwrap();
+    let error = rate_limiter.execute_async(|| async { Ok::<_, Error>(()) }).await.unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!("Rate limiter must be built 'as_asynchronous' to wait in an asynchronous context", error.description);
+    assert_eq!(1, rate_limiter.available_permits());
+}
@@ -0,0 +1,82 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder, State};
+use crate::error::{Error, ErrorKind};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerRegistry {
+    default: CircuitBreakerBuilder,
+    circuit_breakers: Arc<Mutex<BTreeMap<String, Arc<Mutex<CircuitBreaker>>>>>,
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new(default: CircuitBreakerBuilder) -> Self {
+        Self {
+            default,
+            circuit_breakers: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    pub fn get_or_create(&self, name: &str) -> Result<Arc<Mutex<CircuitBreaker>>, Error> {
+        let mut circuit_breakers = self.circuit_breakers.lock().unwrap();
+        if let Some(circuit_breaker) = circuit_breakers.get(name) {
+            return Ok(circuit_breaker.clone());
+        }
+        let circuit_breaker = Arc::new(Mutex::new(self.default.clone().with_name(name).build()?));
+        circuit_breakers.insert(name.to_string(), circuit_breaker.clone());
+        Ok(circuit_breaker)
+    }
+
+    pub fn register(&self, circuit_breaker: CircuitBreaker) -> Result<Arc<Mutex<CircuitBreaker>>, Error> {
+        let name = match circuit_breaker.name() {
+            Some(name) => name.to_string(),
+            None => return Err(Error::new(ErrorKind::Configuration, "Circuit breaker name is required".to_string())),
+        };
+        match self.circuit_breakers.lock().unwrap().entry(name) {
+            Entry::Occupied(entry) => Err(Error::new(
+                ErrorKind::Configuration,
+                format!("Circuit breaker '{}' is already registered", entry.key()),
+            )),
+            Entry::Vacant(entry) => Ok(entry.insert(Arc::new(Mutex::new(circuit_breaker))).clone()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<Mutex<CircuitBreaker>>> {
+        self.circuit_breakers.lock().unwrap().get(name).cloned()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.circuit_breakers.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub fn states(&self) -> Vec<(String, State)> {
+        self.entries()
+            .into_iter()
+            .map(|(name, circuit_breaker)| (name, circuit_breaker.lock().unwrap().state().clone()))
+            .collect()
+    }
+
+    pub fn reset(&self, name: &str) -> bool {
+        match self.get(name) {
+            Some(circuit_breaker) => {
+                circuit_breaker.lock().unwrap().reset();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn reset_all(&self) {
+        for (_, circuit_breaker) in self.entries() {
+            circuit_breaker.lock().unwrap().reset();
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, Arc<Mutex<CircuitBreaker>>)> {
+        self.circuit_breakers.lock().unwrap()
+            .iter()
+            .map(|(name, circuit_breaker)| (name.clone(), circuit_breaker.clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder, State};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{circuit_breaker, circuit_breaker_builder, failed_operation};
+use crate::registry::CircuitBreakerRegistry;
+use rstest::{fixture, rstest};
+use std::sync::Arc;
+
+#[fixture]
+fn registry(circuit_breaker_builder: CircuitBreakerBuilder) -> CircuitBreakerRegistry {
+    CircuitBreakerRegistry::new(circuit_breaker_builder)
+}
+
+#[rstest]
+fn shares_circuit_breaker_per_name(registry: CircuitBreakerRegistry) {
+    let first = registry.get_or_create("payments-api").unwrap();
+    let second = registry.get_or_create("payments-api").unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(Some("payments-api"), first.lock().unwrap().name());
+    assert!(registry.get("inventory-api").is_none());
+}
+
+#[rstest]
+fn lists_states_by_name(registry: CircuitBreakerRegistry) {
+    registry.get_or_create("payments-api").unwrap().lock().unwrap().open();
+    registry.get_or_create("inventory-api").unwrap();
+    assert_eq!(
+        vec![
+            ("inventory-api".to_string(), State::Closed),
+            ("payments-api".to_string(), State::Open),
+        ],
+        registry.states()
+    );
+}
+
+#[rstest]
+fn resets_one_or_all(registry: CircuitBreakerRegistry) {
+    registry.get_or_create("payments-api").unwrap().lock().unwrap().open();
+    registry.get_or_create("inventory-api").unwrap().lock().unwrap().open();
+    assert!(registry.reset("payments-api"));
+    assert!(!registry.reset("unknown-api"));
+    assert_eq!(State::Closed, *registry.get("payments-api").unwrap().lock().unwrap().state());
+    assert_eq!(State::Open, *registry.get("inventory-api").unwrap().lock().unwrap().state());
+    registry.reset_all();
+    assert!(registry.states().iter().all(|(_, state)| *state == State::Closed));
+}
+
+#[rstest]
+fn names_appear_in_display_and_errors(
+    registry: CircuitBreakerRegistry,
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let circuit_breaker = registry.get_or_create("payments-api").unwrap();
+    let mut circuit_breaker = circuit_breaker.lock().unwrap();
+    circuit_breaker.open();
+    let error = circuit_breaker.retry(&mut failed_operation).unwrap_err();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    assert_eq!("Circuit breaker 'payments-api' is open", error.to_string());
+    assert_eq!("Name: payments-api, Attempts: 0/1, Failures: 0/2, State: Open", circuit_breaker.to_string());
+}
+
+#[rstest]
+fn registers_only_named_circuit_breakers(
+    registry: CircuitBreakerRegistry,
+    circuit_breaker: CircuitBreaker,
+    mut circuit_breaker_builder: CircuitBreakerBuilder
+) {
+    assert_eq!(ErrorKind::Configuration, registry.register(circuit_breaker.clone()).unwrap_err().kind());
+    let named = circuit_breaker_builder.with_name("search-api").build().unwrap();
+    let registered = registry.register(named).unwrap();
+    assert!(Arc::ptr_eq(&registered, &registry.get("search-api").unwrap()));
+}
+
+#[rstest]
+fn rejects_duplicate_registration(registry: CircuitBreakerRegistry, mut circuit_breaker_builder: CircuitBreakerBuilder) {
+    let existing = registry.get_or_create("payments-api").unwrap();
+    let duplicate = circuit_breaker_builder.with_name("payments-api").build().unwrap();
+    let error = registry.register(duplicate).unwrap_err();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!("Circuit breaker 'payments-api' is already registered", error.description);
+    assert!(Arc::ptr_eq(&existing, &registry.get("payments-api").unwrap()));
+}
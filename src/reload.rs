@@ -0,0 +1,148 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder};
+use crate::error::{Error, ErrorKind};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+type Parser = dyn Fn(&str) -> Result<CircuitBreakerBuilder, Error> + Send;
+
+pub struct ConfigWatcherBuilder {
+    path: Option<PathBuf>,
+    interval: Duration,
+    parser: Option<Box<Parser>>,
+}
+
+impl Default for ConfigWatcherBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigWatcherBuilder {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            interval: Duration::from_secs(1),
+            parser: None,
+        }
+    }
+
+    pub fn with_path<P: Into<PathBuf>>(&mut self, path: P) -> &mut Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_interval(&mut self, interval: Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_parser<F>(&mut self, parser: F) -> &mut Self
+    where
+        F: Fn(&str) -> Result<CircuitBreakerBuilder, Error> + Send + 'static,
+    {
+        self.parser = Some(Box::new(parser));
+        self
+    }
+
+    pub fn watch(&mut self, circuit_breaker: Arc<Mutex<CircuitBreaker>>) -> Result<ConfigWatcher, Error> {
+        let path = match self.path.take() {
+            Some(path) => path,
+            None => return Err(Error::new(ErrorKind::Configuration, "Config path is required".to_string())),
+        };
+        let parser = match self.parser.take() {
+            Some(parser) => parser,
+            None => return Err(Error::new(ErrorKind::Configuration, "Config parser is required".to_string())),
+        };
+        let stopped = Arc::new(AtomicBool::new(false));
+        let last_error = Arc::new(Mutex::new(None));
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let mut watch = Watch {
+            path,
+            parser,
+            circuit_breaker,
+            last_content: None,
+            last_error: last_error.clone(),
+            reloads: reloads.clone(),
+        };
+        let interval = self.interval;
+        let thread_stopped = stopped.clone();
+        let handle = std::thread::spawn(move || {
+            while !thread_stopped.load(Ordering::SeqCst) {
+                watch.check();
+                std::thread::park_timeout(interval);
+            }
+        });
+        Ok(ConfigWatcher {
+            stopped,
+            last_error,
+            reloads,
+            handle: Some(handle),
+        })
+    }
+}
+
+struct Watch {
+    path: PathBuf,
+    parser: Box<Parser>,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    last_content: Option<String>,
+    last_error: Arc<Mutex<Option<Error>>>,
+    reloads: Arc<AtomicUsize>,
+}
+
+impl Watch {
+    fn check(&mut self) {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) => {
+                *self.last_error.lock().unwrap() = Some(Error::new(ErrorKind::Configuration, format!("Failed to read {}: {}", self.path.display(), error)));
+                return;
+            }
+        };
+        if self.last_content.as_ref() == Some(&content) {
+            return;
+        }
+        let result = (self.parser)(&content)
+            .and_then(|builder| self.circuit_breaker.lock().unwrap().reconfigure(&builder));
+        match result {
+            Ok(()) => {
+                *self.last_error.lock().unwrap() = None;
+                self.reloads.fetch_add(1, Ordering::SeqCst);
+            }
+            Err(error) => *self.last_error.lock().unwrap() = Some(error),
+        }
+        self.last_content = Some(content);
+    }
+}
+
+pub struct ConfigWatcher {
+    stopped: Arc<AtomicBool>,
+    last_error: Arc<Mutex<Option<Error>>>,
+    reloads: Arc<AtomicUsize>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    pub fn last_error(&self) -> Option<Error> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    pub fn reloads(&self) -> usize {
+        self.reloads.load(Ordering::SeqCst)
+    }
+
+    pub fn stop(self) {}
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
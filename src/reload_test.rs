@@ -0,0 +1,82 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder, State};
+use crate::config::CircuitBreakerConfig;
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{circuit_breaker, TIME};
+use crate::reload::{ConfigWatcher, ConfigWatcherBuilder};
+use rstest::rstest;
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
+
+fn parse(content: &str) -> Result<CircuitBreakerBuilder, Error> {
+    let config: CircuitBreakerConfig = toml::from_str(content).map_err(|error| Error::new(ErrorKind::Configuration, error.to_string()))?;
+    CircuitBreakerBuilder::try_from(config)
+}
+
+fn write(file: &NamedTempFile, content: &str) {
+    std::fs::write(file.path(), content).unwrap();
+}
+
+fn wait_for_reloads(watcher: &ConfigWatcher, reloads: usize) {
+    for _ in 0..50 {
+        if watcher.reloads() >= reloads {
+            return;
+        }
+        std::thread::sleep(TIME / 10);
+    }
+    panic!("Expected {} reload(s), got {}", reloads, watcher.reloads());
+}
+
+#[rstest]
+fn applies_changes_from_file(circuit_breaker: CircuitBreaker) {
+    let file = NamedTempFile::new().unwrap();
+    write(&file, "failure_threshold = 4\nreset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"10ms\" } }\n");
+    let shared = Arc::new(Mutex::new(circuit_breaker));
+    shared.lock().unwrap().record_failure();
+    let watcher = ConfigWatcherBuilder::new()
+        .with_path(file.path())
+        .with_interval(TIME / 10)
+        .with_parser(parse)
+        .watch(shared.clone())
+        .unwrap();
+    wait_for_reloads(&watcher, 1);
+    assert_eq!("Attempts: 0/1, Failures: 1/4, State: Closed", shared.lock().unwrap().to_string());
+    write(&file, "failure_threshold = 2\nreset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"10ms\" } }\n");
+    wait_for_reloads(&watcher, 2);
+    shared.lock().unwrap().record_failure();
+    assert_eq!(State::Open, *shared.lock().unwrap().state());
+    watcher.stop();
+}
+
+#[rstest]
+fn keeps_last_good_configuration(circuit_breaker: CircuitBreaker) {
+    let file = NamedTempFile::new().unwrap();
+    write(&file, "failure_threshold = 1\nreset_timeout = \"1s\"\nbackoff = { time = { type = \"constant\", duration = \"10ms\" } }\n");
+    let shared = Arc::new(Mutex::new(circuit_breaker));
+    let watcher = ConfigWatcherBuilder::new()
+        .with_path(file.path())
+        .with_interval(TIME / 10)
+        .with_parser(parse)
+        .watch(shared.clone())
+        .unwrap();
+    for _ in 0..50 {
+        if watcher.last_error().is_some() {
+            break;
+        }
+        std::thread::sleep(TIME / 10);
+    }
+    assert_eq!(
+        "failure_threshold: Failure threshold must be greater than one",
+        watcher.last_error().unwrap().description
+    );
+    assert_eq!(0, watcher.reloads());
+    assert_eq!("Attempts: 0/2, Failures: 0/2, State: Closed", shared.lock().unwrap().to_string());
+}
+
+#[rstest]
+fn requires_path_and_parser(circuit_breaker: CircuitBreaker) {
+    let shared = Arc::new(Mutex::new(circuit_breaker));
+    let error = ConfigWatcherBuilder::new().with_parser(parse).watch(shared.clone()).err().unwrap();
+    assert_eq!("Config path is required", error.description);
+    let error = ConfigWatcherBuilder::new().with_path("config.toml").watch(shared).err().unwrap();
+    assert_eq!("Config parser is required", error.description);
+}
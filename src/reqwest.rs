@@ -0,0 +1,154 @@
+use crate::backoff::Backoff;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::telemetry;
+use async_trait::async_trait;
+use http::header::RETRY_AFTER;
+use http::{Extensions, StatusCode};
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+pub struct CircuitBreakerMiddleware {
+    template: CircuitBreaker,
+    circuit_breakers: Mutex<HashMap<String, Arc<Mutex<CircuitBreaker>>>>,
+    max_retry_after: Duration,
+}
+
+impl CircuitBreakerMiddleware {
+    pub fn new(circuit_breaker: CircuitBreaker) -> Self {
+        let max_retry_after = circuit_breaker.backoff().max_time().unwrap_or(DEFAULT_MAX_RETRY_AFTER);
+        Self {
+            template: circuit_breaker,
+            circuit_breakers: Mutex::new(HashMap::new()),
+            max_retry_after,
+        }
+    }
+
+    pub fn with_max_retry_after(mut self, duration: Duration) -> Self {
+        self.max_retry_after = duration;
+        self
+    }
+
+    pub fn circuit_breaker(&self, host: &str) -> Option<Arc<Mutex<CircuitBreaker>>> {
+        self.circuit_breakers.lock().unwrap().get(host).cloned()
+    }
+
+    fn circuit_breaker_for(&self, host: String) -> Arc<Mutex<CircuitBreaker>> {
+        self.circuit_breakers.lock().unwrap()
+            .entry(host)
+            .or_insert_with(|| Arc::new(Mutex::new(self.template.clone())))
+            .clone()
+    }
+
+    fn host(request: &Request) -> String {
+        let url = request.url();
+        match url.port_or_known_default() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    fn is_failure(result: &reqwest_middleware::Result<Response>) -> bool {
+        match result {
+            Ok(response) => {
+                response.status().is_server_error() || response.status() == StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(reqwest_middleware::Error::Reqwest(error)) => error.is_connect() || error.is_timeout(),
+            Err(_) => false,
+        }
+    }
+
+    fn attempt_failed(name: Option<&str>, attempt: usize, delay: Duration, result: &reqwest_middleware::Result<Response>) {
+        match result {
+            Ok(response) => telemetry::attempt_failed(name, attempt, delay, &response.status()),
+            Err(error) => telemetry::attempt_failed(name, attempt, delay, error),
+        }
+    }
+
+    fn withdraw(backoff: &Backoff, result: &reqwest_middleware::Result<Response>) -> bool {
+        match result {
+            Err(reqwest_middleware::Error::Reqwest(error)) if error.is_timeout() => backoff.withdraw_timeout(),
+            _ => backoff.withdraw(),
+        }
+    }
+
+    fn retry_after(&self, result: &reqwest_middleware::Result<Response>) -> Duration {
+        let value = match result {
+            Ok(response) => response.headers().get(RETRY_AFTER).and_then(|value| value.to_str().ok()),
+            Err(_) => None,
+        };
+        let retry_after = match value {
+            Some(value) => match value.trim().parse::<u64>() {
+                Ok(seconds) => Duration::from_secs(seconds),
+                Err(_) => httpdate::parse_http_date(value)
+                    .ok()
+                    .and_then(|date| date.duration_since(SystemTime::now()).ok())
+                    .unwrap_or_default(),
+            },
+            None => Duration::ZERO,
+        };
+        retry_after.min(self.max_retry_after)
+    }
+}
+
+#[async_trait]
+impl Middleware for CircuitBreakerMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let circuit_breaker = self.circuit_breaker_for(Self::host(&request));
+        let (mut backoff, name, span) = {
+            let circuit_breaker = circuit_breaker.lock().unwrap();
+            let span = telemetry::circuit_breaker_span(circuit_breaker.name(), circuit_breaker.state());
+            (circuit_breaker.backoff().clone(), circuit_breaker.name().map(str::to_string), span)
+        };
+        backoff.reset();
+        let started_at = Instant::now();
+        telemetry::call_started(name.as_deref());
+        let result = telemetry::instrument(span, async {
+            if let Some(retry_budget) = &backoff.retry_budget {
+                retry_budget.deposit();
+            }
+            let mut request = Some(request);
+            let mut attempt = 1;
+            loop {
+                CircuitBreaker::synchronize_shared(&circuit_breaker).await;
+                let rejection = circuit_breaker.lock().unwrap().current_rejection();
+                if let Some(error) = rejection {
+                    telemetry::rejected(name.as_deref(), &error);
+                    return Err(reqwest_middleware::Error::middleware(error));
+                }
+                let current = request.take().unwrap();
+                request = current.try_clone();
+                telemetry::attempt_started(name.as_deref(), attempt);
+                let result = next.clone().run(current, extensions).await;
+                if !Self::is_failure(&result) {
+                    telemetry::attempt_succeeded(name.as_deref(), attempt);
+                    backoff.refund(attempt);
+                    circuit_breaker.lock().unwrap().succeed();
+                    CircuitBreaker::synchronize_shared(&circuit_breaker).await;
+                    return result;
+                }
+                circuit_breaker.lock().unwrap().fail();
+                CircuitBreaker::synchronize_shared(&circuit_breaker).await;
+                if request.is_none() || attempt >= backoff.attempts || !Self::withdraw(&backoff, &result) {
+                    Self::attempt_failed(name.as_deref(), attempt, Duration::ZERO, &result);
+                    return result;
+                }
+                let delay = backoff.advance(self.retry_after(&result));
+                Self::attempt_failed(name.as_deref(), attempt, delay, &result);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }).await;
+        telemetry::call_finished(name.as_deref(), started_at.elapsed());
+        result
+    }
+}
@@ -0,0 +1,134 @@
+use crate::circuit_breaker::{CircuitBreaker, State};
+use crate::fixtures::{retrying_circuit_breaker, TIME};
+use crate::reqwest::CircuitBreakerMiddleware;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use rstest::rstest;
+use std::sync::Arc;
+use std::time::Instant;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(middleware: Arc<CircuitBreakerMiddleware>) -> ClientWithMiddleware {
+    ClientBuilder::new(reqwest::Client::new())
+        .with_arc(middleware)
+        .build()
+}
+
+fn host(server: &MockServer) -> String {
+    server.address().to_string()
+}
+
+#[rstest]
+#[tokio::test]
+async fn retries_server_errors(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let middleware = Arc::new(CircuitBreakerMiddleware::new(circuit_breaker));
+    let response = client(middleware.clone()).get(server.uri()).send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(3, server.received_requests().await.unwrap().len());
+    let circuit_breaker = middleware.circuit_breaker(&host(&server)).unwrap();
+    assert_eq!(State::Closed, circuit_breaker.lock().unwrap().state);
+}
+
+#[rstest]
+#[tokio::test]
+async fn does_not_retry_client_errors(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let middleware = Arc::new(CircuitBreakerMiddleware::new(circuit_breaker));
+    let response = client(middleware).get(server.uri()).send().await.unwrap();
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!(1, server.received_requests().await.unwrap().len());
+}
+
+#[rstest]
+#[tokio::test]
+async fn honors_retry_after(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let middleware = Arc::new(CircuitBreakerMiddleware::new(circuit_breaker));
+    let started_at = Instant::now();
+    let response = client(middleware).get(server.uri()).send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert!(started_at.elapsed() >= std::time::Duration::from_secs(1));
+}
+
+#[rstest]
+#[tokio::test]
+async fn caps_retry_after(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "86400"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let middleware = CircuitBreakerMiddleware::new(circuit_breaker).with_max_retry_after(TIME);
+    let started_at = Instant::now();
+    let response = client(Arc::new(middleware)).get(server.uri()).send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert!(started_at.elapsed() >= TIME);
+    assert!(started_at.elapsed() < TIME * 10);
+}
+
+#[rstest]
+#[tokio::test]
+async fn opens_per_host(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let failing = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&failing)
+        .await;
+    let healthy = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&healthy)
+        .await;
+    let middleware = Arc::new(CircuitBreakerMiddleware::new(circuit_breaker));
+    let client = client(middleware.clone());
+    let response = client.get(failing.uri()).send().await.unwrap();
+    assert_eq!(500, response.status().as_u16());
+    let error = client.get(failing.uri()).send().await.unwrap_err();
+    assert!(error.to_string().contains("Circuit breaker is open"));
+    assert_eq!(3, failing.received_requests().await.unwrap().len());
+    let response = client.get(healthy.uri()).send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    let circuit_breaker = middleware.circuit_breaker(&host(&failing)).unwrap();
+    assert_eq!(State::Open, circuit_breaker.lock().unwrap().state);
+}
+
+#[rstest]
+#[tokio::test]
+async fn retries_connection_errors(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+    let middleware = Arc::new(CircuitBreakerMiddleware::new(circuit_breaker));
+    let result = client(middleware.clone()).get(format!("http://{}", address)).send().await;
+    assert!(result.unwrap_err().is_connect());
+    let circuit_breaker = middleware.circuit_breaker(&address.to_string()).unwrap();
+    assert_eq!(State::Open, circuit_breaker.lock().unwrap().state);
+}
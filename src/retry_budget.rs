@@ -0,0 +1,310 @@
+use crate::duration;
+use crate::error::{Error, ErrorKind};
+use crate::snapshot::SlotSnapshot;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SLOTS: u32 = 10;
+
+#[derive(Debug)]
+struct Slot {
+    started_at: Instant,
+    deposits: usize,
+    withdrawals: usize,
+}
+
+#[derive(Debug)]
+struct Window {
+    ttl: Duration,
+    slots: VecDeque<Slot>,
+}
+
+impl Window {
+    fn current(&mut self, now: Instant) -> &mut Slot {
+        let ttl = self.ttl;
+        while self.slots.front().is_some_and(|slot| now.saturating_duration_since(slot.started_at) >= ttl) {
+            self.slots.pop_front();
+        }
+        let slot_duration = ttl / SLOTS;
+        let expired = self.slots.back()
+            .is_none_or(|slot| now.saturating_duration_since(slot.started_at) >= slot_duration);
+        if expired {
+            self.slots.push_back(Slot {
+                started_at: now,
+                deposits: 0,
+                withdrawals: 0,
+            });
+        }
+        self.slots.back_mut().unwrap()
+    }
+
+    fn totals(&self) -> (usize, usize) {
+        self.slots.iter().fold((0, 0), |(deposits, withdrawals), slot| {
+            (deposits + slot.deposits, withdrawals + slot.withdrawals)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TokenBucket {
+    capacity: usize,
+    retry_cost: usize,
+    timeout_cost: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Limit {
+    Window {
+        min_retries: f64,
+        percent_can_retry: f64,
+        window: Arc<Mutex<Window>>,
+    },
+    Tokens {
+        bucket: TokenBucket,
+        tokens: Arc<Mutex<usize>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryBudgetBuilder {
+    ttl: Duration,
+    min_retries_per_second: usize,
+    percent_can_retry: f32,
+    token_bucket: Option<TokenBucket>,
+}
+
+impl Default for RetryBudgetBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryBudgetBuilder {
+    pub fn new() -> Self {
+        Self {
+            ttl: Duration::from_secs(10),
+            min_retries_per_second: 10,
+            percent_can_retry: 0.2,
+            token_bucket: None,
+        }
+    }
+
+    pub fn with_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_min_retries_per_second(&mut self, retries: usize) -> &mut Self {
+        self.min_retries_per_second = retries;
+        self
+    }
+
+    pub fn with_percent_can_retry(&mut self, percent: f32) -> &mut Self {
+        self.percent_can_retry = percent;
+        self
+    }
+
+    pub fn with_token_bucket(&mut self, capacity: usize, retry_cost: usize, timeout_cost: usize) -> &mut Self {
+        self.token_bucket = Some(TokenBucket {
+            capacity,
+            retry_cost,
+            timeout_cost,
+        });
+        self
+    }
+
+    pub fn build(&self) -> Result<RetryBudget, Error> {
+        if let Some(bucket) = self.token_bucket {
+            if bucket.retry_cost == 0 || bucket.timeout_cost == 0 {
+                return Err(Error::new(ErrorKind::Configuration, "Token costs must be greater than 0".to_string()))
+            }
+            if bucket.capacity < bucket.retry_cost.max(bucket.timeout_cost) {
+                return Err(Error::new(ErrorKind::Configuration, "Token capacity must cover the retry and timeout costs".to_string()))
+            }
+            return Ok(RetryBudget {
+                limit: Limit::Tokens {
+                    bucket,
+                    tokens: Arc::new(Mutex::new(bucket.capacity)),
+                },
+            })
+        }
+        if self.ttl < Duration::from_secs(1) || self.ttl > Duration::from_secs(60) {
+            return Err(Error::new(ErrorKind::Configuration, "TTL must be between 1 and 60 seconds".to_string()))
+        }
+        if !(0.0..=1000.0).contains(&self.percent_can_retry) {
+            return Err(Error::new(ErrorKind::Configuration, "Percent can retry must be between 0.0 and 1000.0".to_string()))
+        }
+        Ok(RetryBudget {
+            limit: Limit::Window {
+                min_retries: self.min_retries_per_second as f64 * self.ttl.as_secs_f64(),
+                percent_can_retry: self.percent_can_retry as f64,
+                window: Arc::new(Mutex::new(Window {
+                    ttl: self.ttl,
+                    slots: VecDeque::with_capacity(SLOTS as usize + 1),
+                })),
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    limit: Limit,
+}
+
+impl RetryBudget {
+    pub fn deposit(&self) {
+        if let Limit::Window { window, .. } = &self.limit {
+            window.lock().unwrap().current(Instant::now()).deposits += 1;
+        }
+    }
+
+    pub fn try_withdraw(&self) -> bool {
+        match &self.limit {
+            Limit::Window { window, .. } => {
+                let mut window = window.lock().unwrap();
+                window.current(Instant::now());
+                if self.available(&window) < 1 {
+                    return false;
+                }
+                window.current(Instant::now()).withdrawals += 1;
+                true
+            }
+            Limit::Tokens { bucket, .. } => self.take(bucket.retry_cost),
+        }
+    }
+
+    pub fn try_withdraw_timeout(&self) -> bool {
+        match &self.limit {
+            Limit::Window { .. } => self.try_withdraw(),
+            Limit::Tokens { bucket, .. } => self.take(bucket.timeout_cost),
+        }
+    }
+
+    pub fn refund(&self, retried: bool) {
+        if let Limit::Tokens { bucket, tokens } = &self.limit {
+            let mut tokens = tokens.lock().unwrap();
+            let refund = if retried { bucket.retry_cost } else { 1 };
+            *tokens = (*tokens + refund).min(bucket.capacity);
+        }
+    }
+
+    pub fn balance(&self) -> usize {
+        match &self.limit {
+            Limit::Window { window, .. } => {
+                let mut window = window.lock().unwrap();
+                window.current(Instant::now());
+                self.available(&window)
+            }
+            Limit::Tokens { tokens, .. } => *tokens.lock().unwrap(),
+        }
+    }
+
+    pub(crate) fn isolated(&self) -> RetryBudget {
+        let limit = match &self.limit {
+            Limit::Window { min_retries, percent_can_retry, window } => Limit::Window {
+                min_retries: *min_retries,
+                percent_can_retry: *percent_can_retry,
+                window: Arc::new(Mutex::new(Window {
+                    ttl: window.lock().unwrap().ttl,
+                    slots: VecDeque::with_capacity(SLOTS as usize + 1),
+                })),
+            },
+            Limit::Tokens { bucket, .. } => Limit::Tokens {
+                bucket: *bucket,
+                tokens: Arc::new(Mutex::new(bucket.capacity)),
+            },
+        };
+        RetryBudget {
+            limit,
+        }
+    }
+
+    pub(crate) fn has_same_limits(&self, other: &RetryBudget) -> bool {
+        match (&self.limit, &other.limit) {
+            (
+                Limit::Window { min_retries, percent_can_retry, window },
+                Limit::Window { min_retries: other_min_retries, percent_can_retry: other_percent_can_retry, window: other_window },
+            ) => min_retries == other_min_retries
+                && percent_can_retry == other_percent_can_retry
+                && window.lock().unwrap().ttl == other_window.lock().unwrap().ttl,
+            (Limit::Tokens { bucket, .. }, Limit::Tokens { bucket: other_bucket, .. }) => bucket == other_bucket,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<SlotSnapshot> {
+        let Limit::Window { window, .. } = &self.limit else {
+            return Vec::new();
+        };
+        let mut window = window.lock().unwrap();
+        let now = Instant::now();
+        window.current(now);
+        window.slots.iter()
+            .map(|slot| SlotSnapshot {
+                age: now.saturating_duration_since(slot.started_at),
+                deposits: slot.deposits,
+                withdrawals: slot.withdrawals,
+            })
+            .collect()
+    }
+
+    pub(crate) fn restore(&self, slots: &[SlotSnapshot], elapsed: Duration) {
+        let Limit::Window { window, .. } = &self.limit else {
+            return;
+        };
+        let mut window = window.lock().unwrap();
+        let now = Instant::now();
+        window.current(now);
+        if window.totals() != (0, 0) {
+            return;
+        }
+        let ttl = window.ttl;
+        window.slots = slots.iter()
+            .filter(|slot| slot.age + elapsed < ttl)
+            .filter_map(|slot| Some(Slot {
+                started_at: now.checked_sub(slot.age + elapsed)?,
+                deposits: slot.deposits,
+                withdrawals: slot.withdrawals,
+            }))
+            .collect();
+    }
+
+    fn take(&self, cost: usize) -> bool {
+        let Limit::Tokens { tokens, .. } = &self.limit else {
+            return false;
+        };
+        let mut tokens = tokens.lock().unwrap();
+        if *tokens < cost {
+            return false;
+        }
+        *tokens -= cost;
+        true
+    }
+
+    fn available(&self, window: &Window) -> usize {
+        let Limit::Window { min_retries, percent_can_retry, .. } = &self.limit else {
+            return 0;
+        };
+        let (deposits, withdrawals) = window.totals();
+        let allowed = min_retries + percent_can_retry * deposits as f64;
+        (allowed as usize).saturating_sub(withdrawals)
+    }
+}
+
+impl Display for RetryBudget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.limit {
+            Limit::Window { min_retries, percent_can_retry, window } => {
+                let ttl = window.lock().unwrap().ttl;
+                let min_retries_per_second = (min_retries / ttl.as_secs_f64()).round();
+                write!(f, "window:{}:{}:{}", duration::format(&ttl), min_retries_per_second, *percent_can_retry as f32)
+            }
+            Limit::Tokens { bucket, .. } => {
+                write!(f, "tokens:{}:{}:{}", bucket.capacity, bucket.retry_cost, bucket.timeout_cost)
+            }
+        }
+    }
+}
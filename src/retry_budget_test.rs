@@ -0,0 +1,172 @@
+use crate::backoff::BackoffBuilder;
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{failed_operation, TIME};
+use crate::retry_budget::RetryBudgetBuilder;
+use rstest::rstest;
+use std::time::Duration;
+
+#[rstest]
+fn invalid_ttl_in_builder() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_ttl(Duration::from_millis(100))
+        .build();
+    assert_eq!("TTL must be between 1 and 60 seconds", retry_budget.unwrap_err().description);
+}
+
+#[rstest]
+fn withdraw_until_empty() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_ttl(Duration::from_secs(1))
+        .with_min_retries_per_second(2)
+        .with_percent_can_retry(0.5)
+        .build()
+        .unwrap();
+    assert_eq!(2, retry_budget.balance());
+    assert!(retry_budget.try_withdraw());
+    assert!(retry_budget.try_withdraw());
+    assert!(!retry_budget.try_withdraw());
+    retry_budget.deposit();
+    retry_budget.deposit();
+    assert_eq!(1, retry_budget.balance());
+    assert!(retry_budget.try_withdraw());
+    assert!(!retry_budget.try_withdraw());
+}
+
+#[rstest]
+fn shared_between_threads() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_ttl(Duration::from_secs(1))
+        .with_min_retries_per_second(0)
+        .with_percent_can_retry(1.0)
+        .build()
+        .unwrap();
+    let handles = (0..4)
+        .map(|_| {
+            let retry_budget = retry_budget.clone();
+            std::thread::spawn(move || retry_budget.deposit())
+        })
+        .collect::<Vec<_>>();
+    handles.into_iter().for_each(|handle| handle.join().unwrap());
+    assert_eq!(4, retry_budget.balance());
+}
+
+#[rstest]
+fn with_backoff(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_ttl(Duration::from_secs(1))
+        .with_min_retries_per_second(1)
+        .with_percent_can_retry(0.0)
+        .build()
+        .unwrap();
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME / 10)
+        .with_attempts(5)
+        .with_retry_budget(retry_budget.clone())
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let mut calls = 0;
+    let result = backoff.retry(&mut || {
+        calls += 1;
+        failed_operation()
+    });
+    assert!(result.is_err());
+    assert_eq!(2, calls);
+    assert_eq!(0, retry_budget.balance());
+}
+
+#[rstest]
+fn with_circuit_breaker(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_ttl(Duration::from_secs(1))
+        .with_min_retries_per_second(2)
+        .with_percent_can_retry(0.0)
+        .build()
+        .unwrap();
+    let mut circuit_breaker = CircuitBreakerBuilder::new()
+        .with_attempts(2)
+        .with_failure_threshold(2)
+        .with_reset_timeout(TIME / 10)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .with_retry_budget(retry_budget)
+        .build()
+        .unwrap();
+    let mut calls = 0;
+    let history = circuit_breaker.retry_with_history(&mut || {
+        calls += 1;
+        failed_operation()
+    }).unwrap_err();
+    assert_eq!(3, calls);
+    assert_eq!(3, history.records().len());
+}
+
+#[rstest]
+fn invalid_token_bucket_in_builder() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_token_bucket(5, 5, 10)
+        .build();
+    assert_eq!("Token capacity must cover the retry and timeout costs", retry_budget.unwrap_err().description);
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_token_bucket(10, 0, 10)
+        .build();
+    assert_eq!("Token costs must be greater than 0", retry_budget.unwrap_err().description);
+}
+
+#[rstest]
+fn token_bucket_charges_retries_and_timeouts() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_token_bucket(20, 5, 10)
+        .build()
+        .unwrap();
+    assert!(retry_budget.try_withdraw_timeout());
+    assert!(retry_budget.try_withdraw());
+    assert_eq!(5, retry_budget.balance());
+    assert!(!retry_budget.try_withdraw_timeout());
+    retry_budget.deposit();
+    assert_eq!(5, retry_budget.balance());
+    retry_budget.refund(true);
+    retry_budget.refund(false);
+    assert_eq!(11, retry_budget.balance());
+    assert!(retry_budget.try_withdraw_timeout());
+}
+
+#[rstest]
+fn token_bucket_is_capped() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_token_bucket(20, 5, 10)
+        .build()
+        .unwrap();
+    retry_budget.refund(true);
+    assert_eq!(20, retry_budget.balance());
+}
+
+#[rstest]
+fn token_bucket_refunds_successful_retries() {
+    let retry_budget = RetryBudgetBuilder::new()
+        .with_token_bucket(10, 5, 10)
+        .build()
+        .unwrap();
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME / 10)
+        .with_attempts(3)
+        .with_retry_budget(retry_budget.clone())
+        .as_synchronous()
+        .build()
+        .unwrap();
+    let mut calls = 0;
+    let result = backoff.retry(&mut || {
+        calls += 1;
+        match calls {
+            1 => Err(Error::new(ErrorKind::Operation, "Failed".to_string())),
+            _ => Ok(()),
+        }
+    });
+    assert!(result.is_ok());
+    assert_eq!(10, retry_budget.balance());
+}
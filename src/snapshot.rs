@@ -0,0 +1,52 @@
+use crate::circuit_breaker::{Override, State};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlotSnapshot {
+    #[cfg_attr(feature = "serde", serde(with = "crate::duration::serde"))]
+    pub age: Duration,
+    pub deposits: usize,
+    pub withdrawals: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircuitBreakerSnapshot {
+    pub taken_at: SystemTime,
+    pub state: State,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub manual_override: Option<Override>,
+    pub failed_attempts: usize,
+    pub failure_count: usize,
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::duration::serde::option", skip_serializing_if = "Option::is_none"))]
+    pub open_for: Option<Duration>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub retry_budget: Vec<SlotSnapshot>,
+}
+
+impl CircuitBreakerSnapshot {
+    pub fn elapsed(&self) -> Duration {
+        SystemTime::now().duration_since(self.taken_at).unwrap_or(Duration::ZERO)
+    }
+
+    pub(crate) fn refresh(&mut self) {
+        let elapsed = self.elapsed();
+        self.taken_at = SystemTime::now();
+        if let Some(open_for) = &mut self.open_for {
+            *open_for += elapsed;
+        }
+        for slot in &mut self.retry_budget {
+            slot.age += elapsed;
+        }
+    }
+
+    pub(crate) fn set_state(&mut self, state: State) {
+        match state {
+            State::Open if self.state != State::Open => self.open_for = Some(Duration::ZERO),
+            State::Open => {}
+            _ => self.open_for = None,
+        }
+        self.state = state;
+    }
+}
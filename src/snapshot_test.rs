@@ -0,0 +1,112 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder, Override, State};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{circuit_breaker, circuit_breaker_builder, TIME};
+use crate::retry_budget::RetryBudgetBuilder;
+use rstest::rstest;
+use std::time::Duration;
+
+#[rstest]
+fn restores_open_state(
+    mut circuit_breaker: CircuitBreaker,
+    #[from(circuit_breaker_builder)] #[with(2)] builder: CircuitBreakerBuilder
+) {
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    let snapshot = circuit_breaker.snapshot();
+    assert_eq!(State::Open, snapshot.state);
+    assert_eq!(2, snapshot.failure_count);
+    assert!(snapshot.open_for.is_some());
+    let mut restored = builder.restore(&snapshot).unwrap();
+    assert_eq!("Attempts: 0/2, Failures: 2/2, State: Open", restored.to_string());
+    let error = restored.retry(&mut || Ok::<(), Error>(())).err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+}
+
+#[rstest]
+fn adjusts_for_elapsed_time(
+    mut circuit_breaker: CircuitBreaker,
+    #[from(circuit_breaker_builder)] #[with(2)] builder: CircuitBreakerBuilder
+) {
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    let mut snapshot = circuit_breaker.snapshot();
+    snapshot.taken_at -= TIME;
+    let restored = builder.restore(&snapshot).unwrap();
+    let remaining = restored.open_remaining().unwrap();
+    assert!(remaining <= TIME, "Remaining {:?} should account for elapsed time", remaining);
+    snapshot.taken_at -= TIME * 2;
+    let mut restored = builder.restore(&snapshot).unwrap();
+    assert_eq!(None, restored.open_remaining());
+    assert_eq!(State::HalfOpen, *restored.state());
+    assert!(restored.retry(&mut || Ok::<(), Error>(())).is_ok());
+    assert_eq!(State::Closed, *restored.state());
+}
+
+#[rstest]
+fn restores_override(
+    mut circuit_breaker: CircuitBreaker,
+    #[from(circuit_breaker_builder)] #[with(2)] builder: CircuitBreakerBuilder
+) {
+    circuit_breaker.force_open();
+    let restored = builder.restore(&circuit_breaker.snapshot()).unwrap();
+    assert_eq!(Some(&Override::ForcedOpen), restored.manual_override());
+}
+
+#[rstest]
+fn restores_retry_budget_window(#[from(circuit_breaker_builder)] #[with(2)] mut builder: CircuitBreakerBuilder) {
+    let mut retry_budget_builder = RetryBudgetBuilder::new();
+    retry_budget_builder
+        .with_ttl(Duration::from_secs(1))
+        .with_min_retries_per_second(0)
+        .with_percent_can_retry(1.0);
+    let mut circuit_breaker = builder
+        .with_retry_budget(retry_budget_builder.build().unwrap())
+        .build()
+        .unwrap();
+    circuit_breaker.retry(&mut || Ok::<(), Error>(())).unwrap();
+    circuit_breaker.retry(&mut || Ok::<(), Error>(())).unwrap();
+    let mut snapshot = circuit_breaker.snapshot();
+    assert_eq!(2, snapshot.retry_budget.iter().map(|slot| slot.deposits).sum::<usize>());
+    let retry_budget = retry_budget_builder.build().unwrap();
+    builder.with_retry_budget(retry_budget.clone()).restore(&snapshot).unwrap();
+    assert_eq!(2, retry_budget.balance());
+    snapshot.taken_at -= Duration::from_secs(1);
+    let retry_budget = retry_budget_builder.build().unwrap();
+    builder.with_retry_budget(retry_budget.clone()).restore(&snapshot).unwrap();
+    assert_eq!(0, retry_budget.balance());
+}
+
+#[rstest]
+fn keeps_shared_retry_budget_window(#[from(circuit_breaker_builder)] #[with(2)] mut builder: CircuitBreakerBuilder) {
+    let mut retry_budget_builder = RetryBudgetBuilder::new();
+    retry_budget_builder
+        .with_ttl(Duration::from_secs(1))
+        .with_min_retries_per_second(0)
+        .with_percent_can_retry(1.0);
+    let mut circuit_breaker = builder
+        .with_retry_budget(retry_budget_builder.build().unwrap())
+        .build()
+        .unwrap();
+    circuit_breaker.retry(&mut || Ok::<(), Error>(())).unwrap();
+    circuit_breaker.retry(&mut || Ok::<(), Error>(())).unwrap();
+    let snapshot = circuit_breaker.snapshot();
+    let retry_budget = retry_budget_builder.build().unwrap();
+    let mut shared = builder.with_retry_budget(retry_budget.clone()).build().unwrap();
+    shared.retry(&mut || Ok::<(), Error>(())).unwrap();
+    builder.restore(&snapshot).unwrap();
+    assert_eq!(1, retry_budget.balance());
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+fn serializes_snapshot(mut circuit_breaker: CircuitBreaker) {
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    let snapshot = circuit_breaker.snapshot();
+    let text = toml::to_string(&snapshot).unwrap();
+    assert!(text.contains("state = \"open\""), "{}", text);
+    let deserialized: crate::snapshot::CircuitBreakerSnapshot = toml::from_str(&text).unwrap();
+    assert_eq!(snapshot.state, deserialized.state);
+    assert_eq!(snapshot.failure_count, deserialized.failure_count);
+    assert_eq!(snapshot.taken_at, deserialized.taken_at);
+}
@@ -0,0 +1,267 @@
+use crate::circuit_breaker::{Override, State};
+use crate::duration;
+use crate::error::{Error, ErrorKind};
+use crate::snapshot::{CircuitBreakerSnapshot, SlotSnapshot};
+use std::ffi::OsString;
+use std::fmt::{Debug, Display};
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind as IoErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+pub type Update<'a> = dyn FnMut(Option<CircuitBreakerSnapshot>) -> CircuitBreakerSnapshot + 'a;
+
+pub trait StateStore: Debug + Send + Sync {
+    fn load(&self) -> Result<Option<CircuitBreakerSnapshot>, Error>;
+
+    fn update(&self, update: &mut Update<'_>) -> Result<CircuitBreakerSnapshot, Error>;
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Change {
+    State(State),
+    Override(Option<Override>),
+    Success,
+    Failure {
+        threshold: usize,
+    },
+    Reset,
+    Restore(CircuitBreakerSnapshot),
+}
+
+impl Change {
+    fn merge(&self, snapshot: &mut CircuitBreakerSnapshot) {
+        match self {
+            Change::State(state) => snapshot.set_state(state.clone()),
+            Change::Override(manual_override) => snapshot.manual_override = manual_override.clone(),
+            Change::Success => {
+                snapshot.set_state(State::Closed);
+                snapshot.failure_count = 0;
+            }
+            Change::Failure { threshold } => {
+                if snapshot.manual_override == Some(Override::Disabled) {
+                    return;
+                }
+                snapshot.failure_count += 1;
+                if snapshot.failure_count >= *threshold && snapshot.manual_override != Some(Override::ForcedClosed) {
+                    snapshot.set_state(State::Open);
+                }
+            }
+            Change::Reset => {
+                snapshot.set_state(State::Closed);
+                snapshot.failure_count = 0;
+            }
+            Change::Restore(restored) => *snapshot = restored.clone(),
+        }
+    }
+}
+
+pub(crate) struct Synchronization {
+    pub(crate) state_store: Arc<dyn StateStore>,
+    pub(crate) local: CircuitBreakerSnapshot,
+    pub(crate) changes: Vec<Change>,
+}
+
+impl Synchronization {
+    pub(crate) fn run(self) -> Result<Option<CircuitBreakerSnapshot>, Error> {
+        if self.changes.is_empty() {
+            return self.state_store.load();
+        }
+        let updated = self.state_store.update(&mut |shared| match shared {
+            Some(mut snapshot) => {
+                snapshot.refresh();
+                for change in &self.changes {
+                    change.merge(&mut snapshot);
+                }
+                snapshot
+            }
+            None => self.local.clone(),
+        });
+        updated.map(Some)
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn run_async(self) -> Result<Option<CircuitBreakerSnapshot>, Error> {
+        tokio::task::spawn_blocking(move || self.run())
+            .await
+            .unwrap_or_else(|error| Err(Error::new(ErrorKind::StateStore, error.to_string())))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryStateStore {
+    snapshot: Mutex<Option<CircuitBreakerSnapshot>>,
+}
+
+impl InMemoryStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateStore for InMemoryStateStore {
+    fn load(&self) -> Result<Option<CircuitBreakerSnapshot>, Error> {
+        Ok(self.snapshot.lock().unwrap().clone())
+    }
+
+    fn update(&self, update: &mut Update<'_>) -> Result<CircuitBreakerSnapshot, Error> {
+        let mut guard = self.snapshot.lock().unwrap();
+        let snapshot = update(guard.take());
+        *guard = Some(snapshot.clone());
+        Ok(snapshot)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileStateStore {
+    path: PathBuf,
+    lock_path: PathBuf,
+    temporary_path: PathBuf,
+}
+
+impl FileStateStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        Self {
+            lock_path: sibling(&path, ".lock"),
+            temporary_path: sibling(&path, ".tmp"),
+            path,
+        }
+    }
+
+    fn failure(&self, action: &str, error: impl Display) -> Error {
+        Error::new(ErrorKind::StateStore, format!("Failed to {} {}: {}", action, self.path.display(), error))
+    }
+
+    fn read(&self) -> Result<Option<CircuitBreakerSnapshot>, Error> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == IoErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(self.failure("open", error)),
+        };
+        let mut content = String::new();
+        file.read_to_string(&mut content).map_err(|error| self.failure("read", error))?;
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+        decode(&content).map(Some).map_err(|error| self.failure("decode", error))
+    }
+
+    fn write(&self, snapshot: &CircuitBreakerSnapshot) -> Result<(), Error> {
+        let mut file = File::create(&self.temporary_path).map_err(|error| self.failure("write", error))?;
+        file.write_all(encode(snapshot).as_bytes())
+            .and_then(|_| file.sync_data())
+            .and_then(|_| std::fs::rename(&self.temporary_path, &self.path))
+            .map_err(|error| self.failure("write", error))
+    }
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+impl StateStore for FileStateStore {
+    fn load(&self) -> Result<Option<CircuitBreakerSnapshot>, Error> {
+        self.read()
+    }
+
+    fn update(&self, update: &mut Update<'_>) -> Result<CircuitBreakerSnapshot, Error> {
+        let lock = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.lock_path)
+            .map_err(|error| self.failure("open", error))?;
+        lock.lock().map_err(|error| self.failure("lock", error))?;
+        let snapshot = update(self.read()?);
+        self.write(&snapshot)?;
+        Ok(snapshot)
+    }
+}
+
+fn encode(snapshot: &CircuitBreakerSnapshot) -> String {
+    let taken_at = snapshot.taken_at.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+    let mut lines = vec![
+        format!("taken_at={}", taken_at.as_nanos()),
+        format!("state={}", match snapshot.state {
+            State::Closed => "closed",
+            State::Open => "open",
+            State::HalfOpen => "half_open",
+        }),
+        format!("failed_attempts={}", snapshot.failed_attempts),
+        format!("failure_count={}", snapshot.failure_count),
+    ];
+    if let Some(manual_override) = &snapshot.manual_override {
+        lines.push(format!("override={}", match manual_override {
+            Override::ForcedOpen => "forced_open",
+            Override::ForcedClosed => "forced_closed",
+            Override::Disabled => "disabled",
+        }));
+    }
+    if let Some(open_for) = &snapshot.open_for {
+        lines.push(format!("open_for={}", duration::format(open_for)));
+    }
+    for slot in &snapshot.retry_budget {
+        lines.push(format!("slot={},{},{}", duration::format(&slot.age), slot.deposits, slot.withdrawals));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn decode(content: &str) -> Result<CircuitBreakerSnapshot, String> {
+    let mut snapshot = CircuitBreakerSnapshot {
+        taken_at: UNIX_EPOCH,
+        state: State::Closed,
+        manual_override: None,
+        failed_attempts: 0,
+        failure_count: 0,
+        open_for: None,
+        retry_budget: Vec::new(),
+    };
+    for (index, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let invalid = |description: &str| format!("{} on line {}", description, index + 1);
+        let (key, value) = line.split_once('=').ok_or_else(|| invalid("Expected 'key=value'"))?;
+        let count = || value.parse::<usize>().map_err(|_| invalid("Expected a number"));
+        match key {
+            "taken_at" => {
+                let nanos = value.parse::<u64>().map_err(|_| invalid("Expected a timestamp"))?;
+                snapshot.taken_at = UNIX_EPOCH + Duration::from_nanos(nanos);
+            }
+            "state" => {
+                snapshot.state = match value {
+                    "closed" => State::Closed,
+                    "open" => State::Open,
+                    "half_open" => State::HalfOpen,
+                    _ => return Err(invalid("Unknown state")),
+                };
+            }
+            "override" => {
+                snapshot.manual_override = Some(match value {
+                    "forced_open" => Override::ForcedOpen,
+                    "forced_closed" => Override::ForcedClosed,
+                    "disabled" => Override::Disabled,
+                    _ => return Err(invalid("Unknown override")),
+                });
+            }
+            "failed_attempts" => snapshot.failed_attempts = count()?,
+            "failure_count" => snapshot.failure_count = count()?,
+            "open_for" => snapshot.open_for = Some(duration::parse(value).map_err(|error| invalid(&error))?),
+            "slot" => {
+                let fields: Vec<&str> = value.split(',').collect();
+                let [age, deposits, withdrawals] = fields.as_slice() else {
+                    return Err(invalid("Expected 'slot=age,deposits,withdrawals'"));
+                };
+                snapshot.retry_budget.push(SlotSnapshot {
+                    age: duration::parse(age).map_err(|error| invalid(&error))?,
+                    deposits: deposits.parse().map_err(|_| invalid("Expected a number"))?,
+                    withdrawals: withdrawals.parse().map_err(|_| invalid("Expected a number"))?,
+                });
+            }
+            _ => return Err(invalid("Unknown key")),
+        }
+    }
+    Ok(snapshot)
+}
@@ -0,0 +1,138 @@
+use crate::circuit_breaker::{CircuitBreaker, Override, State};
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{backoff_builder, circuit_breaker_builder};
+use crate::state_store::{FileStateStore, InMemoryStateStore, StateStore};
+use rstest::rstest;
+use std::sync::Arc;
+use tempfile::{tempdir, NamedTempFile};
+
+fn shared_circuit_breaker(state_store: Arc<dyn StateStore>, failure_threshold: usize) -> CircuitBreaker {
+    circuit_breaker_builder(1, failure_threshold, backoff_builder::default())
+        .with_state_store(state_store)
+        .build()
+        .unwrap()
+}
+
+#[rstest]
+fn shares_failures_in_memory() {
+    let state_store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+    let mut first = shared_circuit_breaker(state_store.clone(), 2);
+    let mut second = shared_circuit_breaker(state_store.clone(), 2);
+    first.record_failure();
+    assert_eq!(State::Closed, *second.state());
+    second.record_failure();
+    assert_eq!(State::Open, *second.state());
+    let error = first.retry(&mut || Ok::<(), Error>(())).err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    assert_eq!(2, state_store.load().unwrap().unwrap().failure_count);
+}
+
+#[rstest]
+fn shares_state_through_file() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("state");
+    let mut first = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 2);
+    let mut second = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 2);
+    first.force_open();
+    let error = second.retry(&mut || Ok::<(), Error>(())).err().unwrap();
+    assert_eq!("Circuit breaker is forced open", error.description);
+    assert_eq!(Some(&Override::ForcedOpen), second.manual_override());
+    second.clear_override();
+    second.record_failure();
+    second.record_failure();
+    assert!(first.retry(&mut || Ok::<(), Error>(())).is_err());
+    assert_eq!(State::Open, *first.state());
+    assert_eq!(None, first.manual_override());
+    second.record_success();
+    assert!(first.retry(&mut || Ok::<(), Error>(())).is_ok());
+}
+
+#[rstest]
+fn merges_changes_into_shared_state() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("state");
+    let mut first = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 3);
+    let mut second = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 3);
+    first.record_failure();
+    second.record_failure();
+    first.force_open();
+    let snapshot = FileStateStore::new(&path).load().unwrap().unwrap();
+    assert_eq!(2, snapshot.failure_count);
+    assert_eq!(Some(Override::ForcedOpen), snapshot.manual_override);
+    second.record_failure();
+    assert_eq!(State::Open, FileStateStore::new(&path).load().unwrap().unwrap().state);
+}
+
+#[rstest]
+fn ignores_partially_written_file() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("state");
+    let mut circuit_breaker = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 3);
+    circuit_breaker.record_failure();
+    std::fs::write(directory.path().join("state.tmp"), "state=clo").unwrap();
+    assert_eq!(1, FileStateStore::new(&path).load().unwrap().unwrap().failure_count);
+    circuit_breaker.record_failure();
+    assert_eq!(2, FileStateStore::new(&path).load().unwrap().unwrap().failure_count);
+}
+
+#[cfg(feature = "async")]
+#[rstest]
+#[tokio::test]
+async fn shares_state_through_file_as_async() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("state");
+    let mut first = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 2);
+    let mut second = shared_circuit_breaker(Arc::new(FileStateStore::new(&path)), 2);
+    first.record_failure();
+    first.record_failure();
+    let error = second.retry_async(&mut || async { Ok::<(), Error>(()) }).await.err().unwrap();
+    assert_eq!(ErrorKind::CircuitOpen, error.kind());
+    assert_eq!(State::Open, *second.state());
+}
+
+#[rstest]
+fn counts_failures_under_lock() {
+    let directory = tempdir().unwrap();
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let path = directory.path().join("state");
+            std::thread::spawn(move || {
+                let mut circuit_breaker = shared_circuit_breaker(Arc::new(FileStateStore::new(path)), 1000);
+                for _ in 0..25 {
+                    circuit_breaker.record_failure();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let snapshot = FileStateStore::new(directory.path().join("state")).load().unwrap().unwrap();
+    assert_eq!(100, snapshot.failure_count);
+    assert_eq!(State::Closed, snapshot.state);
+}
+
+#[rstest]
+fn round_trips_snapshot_through_file() {
+    let directory = tempdir().unwrap();
+    let state_store = FileStateStore::new(directory.path().join("state"));
+    assert!(state_store.load().unwrap().is_none());
+    let mut circuit_breaker = shared_circuit_breaker(Arc::new(InMemoryStateStore::new()), 2);
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    circuit_breaker.disable();
+    let snapshot = circuit_breaker.snapshot();
+    state_store.update(&mut |_| snapshot.clone()).unwrap();
+    assert_eq!(snapshot, state_store.load().unwrap().unwrap());
+}
+
+#[rstest]
+fn reports_missing_and_corrupted_files() {
+    let file = NamedTempFile::new().unwrap();
+    let missing = FileStateStore::new(file.path().with_extension("missing"));
+    assert!(missing.load().unwrap().is_none());
+    std::fs::write(file.path(), "state=ajar\n").unwrap();
+    let error = FileStateStore::new(file.path()).load().err().unwrap();
+    assert_eq!(ErrorKind::StateStore, error.kind());
+    assert!(error.description.ends_with(": Unknown state on line 1"), "{}", error.description);
+}
@@ -0,0 +1,199 @@
+use crate::circuit_breaker::{Override, State};
+use std::fmt::Display;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(feature = "tracing")]
+pub(crate) type Span = tracing::Span;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    pub(crate) fn entered(self) -> Self {
+        self
+    }
+}
+
+pub(crate) fn backoff_span(attempts: usize) -> Span {
+    #[cfg(feature = "tracing")]
+    return tracing::info_span!("backoff", attempts);
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = attempts;
+        Span
+    }
+}
+
+pub(crate) fn circuit_breaker_span(name: Option<&str>, state: &State) -> Span {
+    #[cfg(feature = "tracing")]
+    return tracing::info_span!("circuit_breaker", name, state = ?state);
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (name, state);
+        Span
+    }
+}
+
+#[cfg(feature = "async")]
+pub(crate) fn hedge_span(max_hedges: usize) -> Span {
+    #[cfg(feature = "tracing")]
+    return tracing::info_span!("hedge", max_hedges);
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = max_hedges;
+        Span
+    }
+}
+
+#[cfg(feature = "async")]
+pub(crate) async fn instrument<F: Future>(span: Span, future: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    return tracing::Instrument::instrument(future, span).await;
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = span;
+        future.await
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn label(name: Option<&str>) -> String {
+    name.unwrap_or_default().to_string()
+}
+
+#[cfg(feature = "metrics")]
+fn state_value(state: &State) -> f64 {
+    match state {
+        State::Closed => 0.0,
+        State::Open => 1.0,
+        State::HalfOpen => 2.0,
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn override_value(manual_override: Option<&Override>) -> f64 {
+    match manual_override {
+        None => 0.0,
+        Some(Override::ForcedOpen) => 1.0,
+        Some(Override::ForcedClosed) => 2.0,
+        Some(Override::Disabled) => 3.0,
+    }
+}
+
+pub(crate) fn call_started(name: Option<&str>) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("whoopsie_calls_total", "name" => label(name)).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = name;
+}
+
+pub(crate) fn call_finished(name: Option<&str>, elapsed: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("whoopsie_call_duration_seconds", "name" => label(name)).record(elapsed);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, elapsed);
+}
+
+pub(crate) fn attempt_started(name: Option<&str>, attempt: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(name, attempt, "attempt started");
+    #[cfg(feature = "metrics")]
+    if attempt > 1 {
+        metrics::counter!("whoopsie_retries_total", "name" => label(name)).increment(1);
+    }
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, attempt);
+}
+
+pub(crate) fn attempt_succeeded(name: Option<&str>, attempt: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(name, attempt, "attempt succeeded");
+    #[cfg(feature = "metrics")]
+    metrics::counter!("whoopsie_successes_total", "name" => label(name)).increment(1);
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, attempt);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = attempt;
+}
+
+pub(crate) fn attempt_failed(name: Option<&str>, attempt: usize, delay: Duration, error: &dyn Display) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(name, attempt, delay = ?delay, error = %error, "attempt failed");
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!("whoopsie_failures_total", "name" => label(name)).increment(1);
+        metrics::histogram!("whoopsie_backoff_delay_seconds", "name" => label(name)).record(delay);
+    }
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, attempt, delay, error);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = (attempt, error);
+}
+
+pub(crate) fn attempt_classified_failed(name: Option<&str>, attempt: usize, delay: Duration) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(name, attempt, delay = ?delay, "attempt failed");
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!("whoopsie_failures_total", "name" => label(name)).increment(1);
+        metrics::histogram!("whoopsie_backoff_delay_seconds", "name" => label(name)).record(delay);
+    }
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, attempt, delay);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = attempt;
+}
+
+pub(crate) fn state_initialized(name: Option<&str>, state: &State) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!("whoopsie_circuit_breaker_state", "name" => label(name)).set(state_value(state));
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, state);
+}
+
+pub(crate) fn state_changed(name: Option<&str>, from: &State, to: &State) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(name, from = ?from, to = ?to, "state changed");
+    #[cfg(feature = "metrics")]
+    metrics::gauge!("whoopsie_circuit_breaker_state", "name" => label(name)).set(state_value(to));
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, from, to);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = from;
+}
+
+pub(crate) fn override_changed(name: Option<&str>, from: Option<&Override>, to: Option<&Override>) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(name, from = ?from, to = ?to, "override changed");
+    #[cfg(feature = "metrics")]
+    metrics::gauge!("whoopsie_circuit_breaker_override", "name" => label(name)).set(override_value(to));
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, from, to);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = from;
+}
+
+pub(crate) fn rejected(name: Option<&str>, error: &dyn Display) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(name, error = %error, "call rejected");
+    #[cfg(feature = "metrics")]
+    metrics::counter!("whoopsie_rejections_total", "name" => label(name)).increment(1);
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, error);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = error;
+}
+
+pub(crate) fn state_store_failed(name: Option<&str>, error: &dyn Display) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(name, error = %error, "state store failed");
+    #[cfg(feature = "metrics")]
+    metrics::counter!("whoopsie_state_store_errors_total", "name" => label(name)).increment(1);
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = (name, error);
+    #[cfg(all(feature = "metrics", not(feature = "tracing")))]
+    let _ = error;
+}
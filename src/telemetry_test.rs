@@ -0,0 +1,112 @@
+use crate::backoff::BackoffBuilder;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder};
+use crate::error::Error;
+#[cfg(feature = "async")]
+use crate::error::ErrorKind;
+use crate::fixtures::{circuit_breaker, failed_operation, failed_operation_then_recovered_at_first_attempt, TIME};
+#[cfg(feature = "async")]
+use crate::policy::AsyncPipelineBuilder;
+use crate::policy::PipelineBuilder;
+use rstest::rstest;
+use tracing_test::traced_test;
+
+#[rstest]
+#[traced_test]
+fn backoff_emits_attempts(
+    mut failed_operation_then_recovered_at_first_attempt: impl FnMut() -> Result<(), Error>
+) {
+    let mut backoff = BackoffBuilder::new()
+        .with_constant_time(TIME / 10)
+        .with_attempts(3)
+        .as_synchronous()
+        .build()
+        .unwrap();
+    assert!(backoff.retry(&mut failed_operation_then_recovered_at_first_attempt).is_ok());
+    assert!(logs_contain("backoff{attempts=3}"));
+    assert!(logs_contain("attempt failed attempt=1 delay=10ms error=Something went wrong."));
+    assert!(logs_contain("attempt succeeded attempt=3"));
+}
+
+#[rstest]
+#[traced_test]
+fn circuit_breaker_emits_transitions_and_rejections(
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let mut circuit_breaker = CircuitBreakerBuilder::new()
+        .with_name("payments-api")
+        .with_failure_threshold(2)
+        .with_reset_timeout(TIME * 2)
+        .with_backoff(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .build()
+        .unwrap();
+    assert!(circuit_breaker.retry(&mut failed_operation).is_err());
+    circuit_breaker.open();
+    assert!(circuit_breaker.retry(&mut failed_operation).is_err());
+    assert!(logs_contain("circuit_breaker{name=\"payments-api\" state=Closed}"));
+    assert!(logs_contain("attempt failed name=\"payments-api\" attempt=2"));
+    assert!(logs_contain("state changed name=\"payments-api\" from=Closed to=Open"));
+    assert!(logs_contain("state changed name=\"payments-api\" from=Open to=HalfOpen"));
+    assert!(logs_contain("call rejected name=\"payments-api\" error=Circuit breaker 'payments-api' is open"));
+}
+
+#[rstest]
+#[traced_test]
+fn circuit_breaker_emits_overrides(circuit_breaker: CircuitBreaker) {
+    let mut circuit_breaker = circuit_breaker;
+    circuit_breaker.force_open();
+    circuit_breaker.clear_override();
+    assert!(logs_contain("override changed from=None to=Some(ForcedOpen)"));
+    assert!(logs_contain("override changed from=Some(ForcedOpen) to=None"));
+}
+
+#[rstest]
+#[traced_test]
+fn pipeline_circuit_breaker_emits_attempts(
+    mut failed_operation: impl FnMut() -> Result<(), Error>
+) {
+    let mut pipeline = PipelineBuilder::new()
+        .with_policy(CircuitBreakerBuilder::new()
+            .with_name("payments-api")
+            .with_reset_timeout(TIME)
+            .with_backoff(BackoffBuilder::new()
+                .with_constant_time(TIME / 10)
+                .as_synchronous()
+                .build()
+                .unwrap()
+            )
+            .build()
+            .unwrap()
+        )
+        .build();
+    assert!(pipeline.execute(&mut failed_operation).is_err());
+    assert!(logs_contain("circuit_breaker{name=\"payments-api\" state=Closed}"));
+    assert!(logs_contain("attempt started name=\"payments-api\" attempt=1"));
+    assert!(logs_contain("attempt failed name=\"payments-api\" attempt=1"));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+#[traced_test]
+async fn async_pipeline_backoff_emits_attempts() {
+    let mut pipeline = AsyncPipelineBuilder::new()
+        .with_policy(BackoffBuilder::new()
+            .with_constant_time(TIME / 10)
+            .with_attempts(2)
+            .as_asynchronous()
+            .build()
+            .unwrap()
+        )
+        .build();
+    let result = pipeline.execute_async(&mut || async {
+        Err::<(), _>(Error::new(ErrorKind::Operation, "Something went wrong.".to_string()))
+    }).await;
+    assert!(result.is_err());
+    assert!(logs_contain("backoff{attempts=2}"));
+    assert!(logs_contain("attempt failed attempt=1 delay=10ms"));
+    assert!(logs_contain("attempt failed attempt=2"));
+}
@@ -0,0 +1,219 @@
+use crate::circuit_breaker::CircuitBreaker;
+use crate::error::Error;
+use crate::policy::BoxFuture;
+use crate::telemetry;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower_layer::Layer;
+use tower_service::Service;
+
+pub trait Classify<Res, E> {
+    fn is_failure(&mut self, result: &Result<Res, E>) -> bool;
+}
+
+impl<F, Res, E> Classify<Res, E> for F
+where
+    F: FnMut(&Result<Res, E>) -> bool,
+{
+    fn is_failure(&mut self, result: &Result<Res, E>) -> bool {
+        self(result)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorClassifier;
+
+impl<Res, E> Classify<Res, E> for ErrorClassifier {
+    fn is_failure(&mut self, result: &Result<Res, E>) -> bool {
+        result.is_err()
+    }
+}
+
+pub trait CloneRequest<Req> {
+    fn clone_request(&mut self, request: &Req) -> Option<Req>;
+}
+
+impl<F, Req> CloneRequest<Req> for F
+where
+    F: FnMut(&Req) -> Option<Req>,
+{
+    fn clone_request(&mut self, request: &Req) -> Option<Req> {
+        self(request)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestCloner;
+
+impl<Req: Clone> CloneRequest<Req> for RequestCloner {
+    fn clone_request(&mut self, request: &Req) -> Option<Req> {
+        Some(request.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerLayer<C = ErrorClassifier, K = RequestCloner> {
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    classifier: C,
+    request_cloner: K,
+}
+
+impl CircuitBreakerLayer {
+    pub fn new(circuit_breaker: CircuitBreaker) -> Self {
+        Self::from_shared(Arc::new(Mutex::new(circuit_breaker)))
+    }
+
+    pub fn from_shared(circuit_breaker: Arc<Mutex<CircuitBreaker>>) -> Self {
+        Self {
+            circuit_breaker,
+            classifier: ErrorClassifier,
+            request_cloner: RequestCloner,
+        }
+    }
+}
+
+impl<C, K> CircuitBreakerLayer<C, K> {
+    pub fn with_classifier<N>(self, classifier: N) -> CircuitBreakerLayer<N, K> {
+        CircuitBreakerLayer {
+            circuit_breaker: self.circuit_breaker,
+            classifier,
+            request_cloner: self.request_cloner,
+        }
+    }
+
+    pub fn with_request_cloner<N>(self, request_cloner: N) -> CircuitBreakerLayer<C, N> {
+        CircuitBreakerLayer {
+            circuit_breaker: self.circuit_breaker,
+            classifier: self.classifier,
+            request_cloner,
+        }
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<Mutex<CircuitBreaker>> {
+        self.circuit_breaker.clone()
+    }
+}
+
+impl<S, C: Clone, K: Clone> Layer<S> for CircuitBreakerLayer<C, K> {
+    type Service = CircuitBreakerService<S, C, K>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CircuitBreakerService {
+            inner,
+            circuit_breaker: self.circuit_breaker.clone(),
+            classifier: self.classifier.clone(),
+            request_cloner: self.request_cloner.clone(),
+            sleep: None,
+        }
+    }
+}
+
+pub struct CircuitBreakerService<S, C = ErrorClassifier, K = RequestCloner> {
+    inner: S,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    classifier: C,
+    request_cloner: K,
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<S: Clone, C: Clone, K: Clone> Clone for CircuitBreakerService<S, C, K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
+            classifier: self.classifier.clone(),
+            request_cloner: self.request_cloner.clone(),
+            sleep: None,
+        }
+    }
+}
+
+impl<S, C, K, Req> Service<Req> for CircuitBreakerService<S, C, K>
+where
+    S: Service<Req> + Clone + Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: From<Error> + Send + 'static,
+    S::Future: Send,
+    C: Classify<S::Response, S::Error> + Clone + Send + 'static,
+    K: CloneRequest<Req> + Clone + Send + 'static,
+    Req: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let remaining = self.circuit_breaker.lock().unwrap().open_remaining();
+        if let Some(remaining) = remaining {
+            let sleep = self.sleep.get_or_insert_with(|| Box::pin(tokio::time::sleep(remaining)));
+            if sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+        self.sleep = None;
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Req) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let circuit_breaker = self.circuit_breaker.clone();
+        let mut classifier = self.classifier.clone();
+        let mut request_cloner = self.request_cloner.clone();
+        let (mut backoff, name, span) = {
+            let circuit_breaker = circuit_breaker.lock().unwrap();
+            let span = telemetry::circuit_breaker_span(circuit_breaker.name(), circuit_breaker.state());
+            (circuit_breaker.backoff().clone(), circuit_breaker.name().map(str::to_string), span)
+        };
+        backoff.reset();
+        Box::pin(async move {
+            let name = name.as_deref();
+            let started_at = Instant::now();
+            telemetry::call_started(name);
+            let result = telemetry::instrument(span, async move {
+                if let Some(retry_budget) = &backoff.retry_budget {
+                    retry_budget.deposit();
+                }
+                let mut attempt = 1;
+                let mut request = Some(request);
+                loop {
+                    CircuitBreaker::synchronize_shared(&circuit_breaker).await;
+                    let rejection = circuit_breaker.lock().unwrap().current_rejection();
+                    if let Some(error) = rejection {
+                        telemetry::rejected(name, &error);
+                        return Err(error.into());
+                    }
+                    if attempt > 1 {
+                        poll_fn(|cx| inner.poll_ready(cx)).await?;
+                    }
+                    telemetry::attempt_started(name, attempt);
+                    let current = request.take().unwrap();
+                    request = request_cloner.clone_request(&current);
+                    let result = inner.call(current).await;
+                    if !classifier.is_failure(&result) {
+                        telemetry::attempt_succeeded(name, attempt);
+                        backoff.refund(attempt);
+                        circuit_breaker.lock().unwrap().succeed();
+                        CircuitBreaker::synchronize_shared(&circuit_breaker).await;
+                        return result;
+                    }
+                    circuit_breaker.lock().unwrap().fail();
+                    CircuitBreaker::synchronize_shared(&circuit_breaker).await;
+                    if attempt >= backoff.attempts || request.is_none() || !backoff.withdraw() {
+                        telemetry::attempt_classified_failed(name, attempt, Duration::ZERO);
+                        return result;
+                    }
+                    let delay = backoff.advance(Duration::ZERO);
+                    telemetry::attempt_classified_failed(name, attempt, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }).await;
+            telemetry::call_finished(name, started_at.elapsed());
+            result
+        })
+    }
+}
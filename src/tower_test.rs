@@ -0,0 +1,112 @@
+use crate::backoff::WaitStrategy;
+use crate::circuit_breaker::{CircuitBreaker, State};
+use crate::fixtures::{backoff_builder, circuit_breaker_builder, retrying_circuit_breaker, TIME};
+use crate::tower::CircuitBreakerLayer;
+use ::tower::{service_fn, Service, ServiceBuilder, ServiceExt};
+use rstest::rstest;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[rstest]
+#[tokio::test]
+async fn retries_classified_responses(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let service = ServiceBuilder::new()
+        .layer(CircuitBreakerLayer::new(circuit_breaker)
+            .with_classifier(|result: &Result<u16, BoxError>| matches!(result, Ok(503)))
+        )
+        .service(service_fn(move |_: ()| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move { Ok::<_, BoxError>(if call < 2 { 503 } else { 200 }) }
+        }));
+    assert_eq!(200, service.oneshot(()).await.unwrap());
+    assert_eq!(3, calls.load(Ordering::SeqCst));
+}
+
+#[rstest]
+#[tokio::test]
+async fn gives_up_with_last_error(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let layer = CircuitBreakerLayer::new(circuit_breaker);
+    let shared = layer.circuit_breaker();
+    let service = ServiceBuilder::new()
+        .layer(layer)
+        .service(service_fn(|request: &'static str| async move {
+            Err::<(), BoxError>(request.into())
+        }));
+    let error = service.oneshot("Something went wrong.").await.unwrap_err();
+    assert_eq!("Something went wrong.", error.to_string());
+    assert_eq!(State::Open, shared.lock().unwrap().state);
+}
+
+#[rstest]
+#[tokio::test]
+async fn not_ready_while_open(#[from(retrying_circuit_breaker)] mut circuit_breaker: CircuitBreaker) {
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    circuit_breaker.record_failure();
+    let mut service = ServiceBuilder::new()
+        .layer(CircuitBreakerLayer::new(circuit_breaker))
+        .service(service_fn(|_: ()| async { Ok::<_, BoxError>(()) }));
+    assert!(tokio::time::timeout(TIME, service.ready()).await.is_err());
+    assert!(tokio::time::timeout(TIME * 3, service.ready()).await.is_ok());
+    assert!(service.call(()).await.is_ok());
+}
+
+#[tokio::test]
+async fn retries_with_synchronous_backoff() {
+    let circuit_breaker = circuit_breaker_builder(1, 3, backoff_builder(TIME / 10, 2, WaitStrategy::Synchronous))
+        .build()
+        .unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let service = ServiceBuilder::new()
+        .layer(CircuitBreakerLayer::new(circuit_breaker))
+        .service(service_fn(move |_: ()| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move { if call < 1 { Err::<(), BoxError>("Something went wrong.".into()) } else { Ok(()) } }
+        }));
+    assert!(service.oneshot(()).await.is_ok());
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+}
+
+struct Streamed(&'static str);
+
+#[rstest]
+#[tokio::test]
+async fn attempts_unclonable_request_once(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let layer = CircuitBreakerLayer::new(circuit_breaker)
+        .with_request_cloner(|_: &Streamed| None);
+    let shared = layer.circuit_breaker();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let service = ServiceBuilder::new()
+        .layer(layer)
+        .service(service_fn(move |request: Streamed| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move { Err::<(), BoxError>(request.0.into()) }
+        }));
+    let error = service.oneshot(Streamed("Something went wrong.")).await.unwrap_err();
+    assert_eq!("Something went wrong.", error.to_string());
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+    assert_eq!(1, shared.lock().unwrap().failure_count);
+}
+
+#[rstest]
+#[tokio::test]
+async fn retries_with_request_cloner(#[from(retrying_circuit_breaker)] circuit_breaker: CircuitBreaker) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let service = ServiceBuilder::new()
+        .layer(CircuitBreakerLayer::new(circuit_breaker)
+            .with_request_cloner(|request: &Streamed| Some(Streamed(request.0)))
+        )
+        .service(service_fn(move |request: Streamed| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move { if call < 2 { Err::<_, BoxError>(request.0.into()) } else { Ok(request.0) } }
+        }));
+    assert_eq!("READY", service.oneshot(Streamed("READY")).await.unwrap());
+    assert_eq!(3, calls.load(Ordering::SeqCst));
+}
@@ -0,0 +1,230 @@
+use crate::backoff::{Backoff, BackoffBuilder, Jitter};
+use crate::bulkhead::Bulkhead;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerBuilder};
+use crate::error::Error;
+use crate::retry_budget::RetryBudget;
+use crate::state_store::StateStore;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct Missing;
+
+pub struct Present;
+
+/// ```
+/// # use std::time::Duration;
+/// # use whoopsie::backoff::BackoffBuilder;
+/// BackoffBuilder::typed().with_constant_time(Duration::from_millis(10)).as_synchronous().build().unwrap();
+/// ```
+///
+/// ```compile_fail
+/// # use whoopsie::backoff::BackoffBuilder;
+/// BackoffBuilder::typed().as_synchronous().build();
+/// ```
+///
+/// ```compile_fail
+/// # use std::time::Duration;
+/// # use whoopsie::backoff::BackoffBuilder;
+/// BackoffBuilder::typed().with_constant_time(Duration::from_millis(10)).build();
+/// ```
+pub struct TypedBackoffBuilder<T, W> {
+    inner: BackoffBuilder,
+    fields: PhantomData<(T, W)>,
+}
+
+impl Default for TypedBackoffBuilder<Missing, Missing> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypedBackoffBuilder<Missing, Missing> {
+    pub fn new() -> Self {
+        Self {
+            inner: BackoffBuilder::new(),
+            fields: PhantomData,
+        }
+    }
+}
+
+impl<T, W> TypedBackoffBuilder<T, W> {
+    fn into_state<NT, NW>(self) -> TypedBackoffBuilder<NT, NW> {
+        TypedBackoffBuilder {
+            inner: self.inner,
+            fields: PhantomData,
+        }
+    }
+
+    pub fn with_constant_time(mut self, duration: Duration) -> TypedBackoffBuilder<Present, W> {
+        self.inner.with_constant_time(duration);
+        self.into_state()
+    }
+
+    pub fn with_exponential_time(mut self, duration: Duration, factor: f32) -> TypedBackoffBuilder<Present, W> {
+        self.inner.with_exponential_time(duration, factor);
+        self.into_state()
+    }
+
+    pub fn as_synchronous(mut self) -> TypedBackoffBuilder<T, Present> {
+        self.inner.as_synchronous();
+        self.into_state()
+    }
+
+    #[cfg(feature = "async")]
+    pub fn as_asynchronous(mut self) -> TypedBackoffBuilder<T, Present> {
+        self.inner.as_asynchronous();
+        self.into_state()
+    }
+
+    pub fn as_spin_loop(mut self) -> TypedBackoffBuilder<T, Present> {
+        self.inner.as_spin_loop();
+        self.into_state()
+    }
+
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        self.inner.with_attempts(attempts);
+        self
+    }
+
+    pub fn with_retry_budget(mut self, retry_budget: RetryBudget) -> Self {
+        self.inner.with_retry_budget(retry_budget);
+        self
+    }
+
+    pub fn with_max_time(mut self, duration: Duration) -> Self {
+        self.inner.with_max_time(duration);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.inner.with_jitter(jitter);
+        self
+    }
+}
+
+impl TypedBackoffBuilder<Present, Present> {
+    pub fn build(self) -> Result<Backoff, Error> {
+        self.inner.build()
+    }
+
+    pub fn into_builder(self) -> BackoffBuilder {
+        self.inner
+    }
+}
+
+/// ```
+/// # use std::time::Duration;
+/// # use whoopsie::backoff::BackoffBuilder;
+/// # use whoopsie::circuit_breaker::CircuitBreakerBuilder;
+/// let backoff = BackoffBuilder::typed().with_constant_time(Duration::from_millis(10)).as_synchronous().into_builder();
+/// CircuitBreakerBuilder::typed().with_reset_timeout(Duration::from_millis(10)).with_backoff_builder(backoff).build().unwrap();
+/// ```
+///
+/// ```compile_fail
+/// # use whoopsie::backoff::BackoffBuilder;
+/// # use whoopsie::circuit_breaker::CircuitBreakerBuilder;
+/// let backoff = BackoffBuilder::typed().with_constant_time(std::time::Duration::from_millis(10)).as_synchronous().into_builder();
+/// CircuitBreakerBuilder::typed().with_backoff_builder(backoff).build();
+/// ```
+///
+/// ```compile_fail
+/// # use std::time::Duration;
+/// # use whoopsie::circuit_breaker::CircuitBreakerBuilder;
+/// CircuitBreakerBuilder::typed().with_reset_timeout(Duration::from_millis(10)).build();
+/// ```
+pub struct TypedCircuitBreakerBuilder<R, B> {
+    inner: CircuitBreakerBuilder,
+    fields: PhantomData<(R, B)>,
+}
+
+impl Default for TypedCircuitBreakerBuilder<Missing, Missing> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypedCircuitBreakerBuilder<Missing, Missing> {
+    pub fn new() -> Self {
+        Self {
+            inner: CircuitBreakerBuilder::new(),
+            fields: PhantomData,
+        }
+    }
+}
+
+impl<R, B> TypedCircuitBreakerBuilder<R, B> {
+    fn into_state<NR, NB>(self) -> TypedCircuitBreakerBuilder<NR, NB> {
+        TypedCircuitBreakerBuilder {
+            inner: self.inner,
+            fields: PhantomData,
+        }
+    }
+
+    pub fn with_reset_timeout(mut self, duration: Duration) -> TypedCircuitBreakerBuilder<Present, B> {
+        self.inner.with_reset_timeout(duration);
+        self.into_state()
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> TypedCircuitBreakerBuilder<R, Present> {
+        self.inner.with_backoff(backoff);
+        self.into_state()
+    }
+
+    pub fn with_backoff_builder(mut self, backoff: BackoffBuilder) -> TypedCircuitBreakerBuilder<R, Present> {
+        self.inner.with_backoff_builder(backoff);
+        self.into_state()
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.inner.with_name(name);
+        self
+    }
+
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        self.inner.with_attempts(attempts);
+        self
+    }
+
+    pub fn with_failure_threshold(mut self, threshold: usize) -> Self {
+        self.inner.with_failure_threshold(threshold);
+        self
+    }
+
+    pub fn with_bulkhead(mut self, bulkhead: Bulkhead) -> Self {
+        self.inner.with_bulkhead(bulkhead);
+        self
+    }
+
+    pub fn with_retry_budget(mut self, retry_budget: RetryBudget) -> Self {
+        self.inner.with_retry_budget(retry_budget);
+        self
+    }
+
+    pub fn with_state_store(mut self, state_store: Arc<dyn StateStore>) -> Self {
+        self.inner.with_state_store(state_store);
+        self
+    }
+}
+
+impl TypedCircuitBreakerBuilder<Present, Present> {
+    pub fn build(self) -> Result<CircuitBreaker, Error> {
+        self.inner.build()
+    }
+
+    pub fn into_builder(self) -> CircuitBreakerBuilder {
+        self.inner
+    }
+}
+
+impl BackoffBuilder {
+    pub fn typed() -> TypedBackoffBuilder<Missing, Missing> {
+        TypedBackoffBuilder::new()
+    }
+}
+
+impl CircuitBreakerBuilder {
+    pub fn typed() -> TypedCircuitBreakerBuilder<Missing, Missing> {
+        TypedCircuitBreakerBuilder::new()
+    }
+}
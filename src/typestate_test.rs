@@ -0,0 +1,74 @@
+use crate::backoff::{BackoffBuilder, Jitter};
+use crate::circuit_breaker::CircuitBreakerBuilder;
+use crate::error::{Error, ErrorKind};
+use crate::fixtures::{failed_operation, TIME};
+use crate::typestate::{TypedBackoffBuilder, TypedCircuitBreakerBuilder};
+use rstest::rstest;
+
+#[rstest]
+fn builds_backoff_in_any_order() {
+    let backoff = BackoffBuilder::typed()
+        .as_synchronous()
+        .with_attempts(3)
+        .with_exponential_time(TIME, 2.0)
+        .with_jitter(Jitter::Equal)
+        .build()
+        .unwrap();
+    assert_eq!("exp(100ms, x2, jitter=equal, attempts=3)", backoff.to_string());
+}
+
+#[rstest]
+fn builds_circuit_breaker(mut failed_operation: impl FnMut() -> Result<(), Error>) {
+    let mut circuit_breaker = CircuitBreakerBuilder::typed()
+        .with_name("db")
+        .with_backoff(TypedBackoffBuilder::new()
+            .with_constant_time(TIME)
+            .as_synchronous()
+            .build()
+            .unwrap()
+        )
+        .with_failure_threshold(2)
+        .with_reset_timeout(TIME)
+        .build()
+        .unwrap();
+    assert!(circuit_breaker.retry(&mut failed_operation).is_err());
+    assert_eq!("Name: db, Attempts: 1/1, Failures: 2/2, State: HalfOpen", circuit_breaker.to_string());
+}
+
+#[rstest]
+fn invalid_values_are_errors() {
+    let error = TypedBackoffBuilder::new()
+        .with_exponential_time(TIME, 0.0)
+        .as_spin_loop()
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::Configuration, error.kind());
+    assert_eq!("Factor must be greater than zero", error.description);
+    let backoff = || BackoffBuilder::typed().with_constant_time(TIME).as_synchronous().build().unwrap();
+    let error = TypedCircuitBreakerBuilder::new()
+        .with_reset_timeout(TIME)
+        .with_backoff(backoff())
+        .with_attempts(0)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!("Attempts must be greater than zero", error.description);
+    let error = TypedCircuitBreakerBuilder::new()
+        .with_reset_timeout(TIME)
+        .with_backoff(backoff())
+        .with_failure_threshold(1)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!("Failure threshold must be greater than one", error.description);
+}
+
+#[rstest]
+fn converts_into_mutable_builder() {
+    let mut builder = TypedBackoffBuilder::new()
+        .with_constant_time(TIME)
+        .as_synchronous()
+        .into_builder();
+    assert_eq!("const(100ms, attempts=2)", builder.with_attempts(2).build().unwrap().to_string());
+}
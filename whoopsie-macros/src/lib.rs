@@ -0,0 +1,289 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Error, Expr, ExprLit, FnArg, ItemFn, Lit, MetaNameValue, Pat, ReturnType, Token, Type};
+
+type Arguments = Punctuated<MetaNameValue, Token![,]>;
+
+#[proc_macro_attribute]
+pub fn retry(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    let arguments = parse_macro_input!(attribute with Arguments::parse_terminated);
+    let function = parse_macro_input!(item as ItemFn);
+    expand_retry(arguments, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn circuit_breaker(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    let arguments = parse_macro_input!(attribute with Arguments::parse_terminated);
+    let function = parse_macro_input!(item as ItemFn);
+    expand_circuit_breaker(arguments, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum Time {
+    Constant(TokenStream2),
+    Exponential(TokenStream2),
+}
+
+fn expand_retry(arguments: Arguments, function: ItemFn) -> syn::Result<TokenStream2> {
+    let mut time = None;
+    let mut factor = None;
+    let mut preset = None;
+    let mut settings = Vec::new();
+    for argument in &arguments {
+        let literal = literal(&argument.value)?;
+        match key(argument)?.as_str() {
+            "constant" => time = Some(Time::Constant(duration(literal)?)),
+            "exp" => time = Some(Time::Exponential(duration(literal)?)),
+            "factor" => {
+                let value = float(literal)?;
+                if value <= 0.0 {
+                    return Err(Error::new_spanned(literal, "Factor must be greater than zero"));
+                }
+                factor = Some(value as f32);
+            }
+            "preset" => preset = Some(string(literal)?),
+            "attempts" => {
+                let attempts = positive(literal, 1, "Attempts must be greater than zero")?;
+                settings.push(quote!(.with_attempts(#attempts)));
+            }
+            "max" => {
+                let max_time = duration(literal)?;
+                settings.push(quote!(.with_max_time(#max_time)));
+            }
+            "jitter" => {
+                let jitter = jitter(literal)?;
+                settings.push(quote!(.with_jitter(#jitter)));
+            }
+            _ => return Err(Error::new_spanned(
+                &argument.path,
+                "Unknown argument, expected one of 'constant', 'exp', 'factor', 'preset', 'attempts', 'max' or 'jitter'",
+            )),
+        }
+    }
+    let builder = match (time, preset) {
+        (Some(Time::Constant(duration)), None) => {
+            if factor.is_some() {
+                return Err(Error::new(Span::call_site(), "'factor' only applies to 'exp'"));
+            }
+            quote!(::whoopsie::backoff::BackoffBuilder::new().with_constant_time(#duration))
+        }
+        (Some(Time::Exponential(duration)), None) => {
+            let factor = factor.unwrap_or(2.0);
+            quote!(::whoopsie::backoff::BackoffBuilder::new().with_exponential_time(#duration, #factor))
+        }
+        (None, Some(preset)) if factor.is_none() => quote!(::whoopsie::backoff::BackoffBuilder::preset(#preset)?),
+        (None, None) => return Err(Error::new(Span::call_site(), "Expected one of 'constant', 'exp' or 'preset'")),
+        _ => return Err(Error::new(Span::call_site(), "Use either 'constant', 'exp' with 'factor', or 'preset'")),
+    };
+    let ItemFn { attrs, vis, sig, block } = function;
+    let output = output(&sig.output)?;
+    let clones = clones(&sig.inputs, sig.asyncness.is_some())?;
+    let (requirement, wait, retry) = if sig.asyncness.is_some() {
+        (
+            quote!(::whoopsie::__require_async!();),
+            quote!(.as_asynchronous()),
+            quote! {
+                __whoopsie_backoff.retry_async(&mut || {
+                    #(#clones)*
+                    async move {
+                        let __whoopsie_output: #output = async move #block.await;
+                        __whoopsie_output
+                    }
+                }).await
+            },
+        )
+    } else {
+        (
+            quote!(),
+            quote!(.as_synchronous()),
+            quote! {
+                __whoopsie_backoff.retry(&mut || -> #output {
+                    #(#clones)*
+                    #block
+                })
+            },
+        )
+    };
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            #requirement
+            static __WHOOPSIE_BACKOFF: ::std::sync::LazyLock<::whoopsie::backoff::Backoff> = ::std::sync::LazyLock::new(|| {
+                let __whoopsie_build = || -> ::std::result::Result<::whoopsie::backoff::Backoff, ::whoopsie::error::Error> {
+                    #builder #(#settings)* #wait .build()
+                };
+                __whoopsie_build().unwrap_or_else(|error| panic!("Invalid retry configuration: {}", error))
+            });
+            let mut __whoopsie_backoff = ::std::clone::Clone::clone(&*__WHOOPSIE_BACKOFF);
+            #retry
+        }
+    })
+}
+
+fn expand_circuit_breaker(arguments: Arguments, function: ItemFn) -> syn::Result<TokenStream2> {
+    let mut preset = "standard".to_string();
+    let mut settings = Vec::new();
+    for argument in &arguments {
+        let literal = literal(&argument.value)?;
+        match key(argument)?.as_str() {
+            "name" => {
+                let name = string(literal)?;
+                settings.push(quote!(.with_name(#name)));
+            }
+            "preset" => preset = string(literal)?,
+            "failure_threshold" => {
+                let threshold = positive(literal, 2, "Failure threshold must be greater than one")?;
+                settings.push(quote!(.with_failure_threshold(#threshold)));
+            }
+            "reset_timeout" => {
+                let reset_timeout = duration(literal)?;
+                settings.push(quote!(.with_reset_timeout(#reset_timeout)));
+            }
+            _ => return Err(Error::new_spanned(
+                &argument.path,
+                "Unknown argument, expected one of 'name', 'preset', 'failure_threshold' or 'reset_timeout'",
+            )),
+        }
+    }
+    let ItemFn { attrs, vis, sig, block } = function;
+    let output = output(&sig.output)?;
+    let (requirement, guard, call, record) = if sig.asyncness.is_some() {
+        (
+            quote!(::whoopsie::__require_async!();),
+            quote!(::whoopsie::macros::guard_async(&__WHOOPSIE_CIRCUIT_BREAKER).await),
+            quote!(async move #block.await),
+            quote!(::whoopsie::macros::record_async(&__WHOOPSIE_CIRCUIT_BREAKER, &__whoopsie_output).await),
+        )
+    } else {
+        (
+            quote!(),
+            quote!(::whoopsie::macros::guard(&__WHOOPSIE_CIRCUIT_BREAKER)),
+            quote!((move || -> #output #block)()),
+            quote!(::whoopsie::macros::record(&__WHOOPSIE_CIRCUIT_BREAKER, &__whoopsie_output)),
+        )
+    };
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            #requirement
+            static __WHOOPSIE_CIRCUIT_BREAKER: ::std::sync::LazyLock<
+                ::std::sync::Mutex<::whoopsie::circuit_breaker::CircuitBreaker>
+            > = ::std::sync::LazyLock::new(|| {
+                let __whoopsie_build = || -> ::std::result::Result<
+                    ::whoopsie::circuit_breaker::CircuitBreaker,
+                    ::whoopsie::error::Error
+                > {
+                    ::whoopsie::circuit_breaker::CircuitBreakerBuilder::preset(#preset)? #(#settings)* .build()
+                };
+                ::std::sync::Mutex::new(
+                    __whoopsie_build().unwrap_or_else(|error| panic!("Invalid circuit breaker configuration: {}", error))
+                )
+            });
+            #guard?;
+            let __whoopsie_output: #output = #call;
+            #record;
+            __whoopsie_output
+        }
+    })
+}
+
+fn key(argument: &MetaNameValue) -> syn::Result<String> {
+    argument.path.get_ident()
+        .map(|ident| ident.to_string())
+        .ok_or_else(|| Error::new_spanned(&argument.path, "Expected an argument name"))
+}
+
+fn literal(value: &Expr) -> syn::Result<&Lit> {
+    match value {
+        Expr::Lit(ExprLit { lit, .. }) => Ok(lit),
+        _ => Err(Error::new_spanned(value, "Expected a literal")),
+    }
+}
+
+fn string(literal: &Lit) -> syn::Result<String> {
+    match literal {
+        Lit::Str(value) => Ok(value.value()),
+        _ => Err(Error::new_spanned(literal, "Expected a string")),
+    }
+}
+
+fn float(literal: &Lit) -> syn::Result<f64> {
+    match literal {
+        Lit::Float(value) => value.base10_parse(),
+        Lit::Int(value) => value.base10_parse(),
+        _ => Err(Error::new_spanned(literal, "Expected a number")),
+    }
+}
+
+fn positive(literal: &Lit, minimum: usize, description: &str) -> syn::Result<usize> {
+    let value = match literal {
+        Lit::Int(value) => value.base10_parse::<usize>()?,
+        _ => return Err(Error::new_spanned(literal, "Expected an integer")),
+    };
+    if value < minimum {
+        return Err(Error::new_spanned(literal, description));
+    }
+    Ok(value)
+}
+
+fn jitter(literal: &Lit) -> syn::Result<TokenStream2> {
+    if let Lit::Str(value) = literal {
+        return match value.value().as_str() {
+            "none" => Ok(quote!(::whoopsie::backoff::Jitter::None)),
+            "full" => Ok(quote!(::whoopsie::backoff::Jitter::Full)),
+            "equal" => Ok(quote!(::whoopsie::backoff::Jitter::Equal)),
+            _ => Err(Error::new_spanned(literal, "Unknown jitter, expected 'none', 'full', 'equal' or a ratio")),
+        };
+    }
+    let ratio = float(literal)?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(Error::new_spanned(literal, "Jitter ratio must be between 0.0 and 1.0"));
+    }
+    let ratio = ratio as f32;
+    Ok(quote!(::whoopsie::backoff::Jitter::Proportional(#ratio)))
+}
+
+fn duration(literal: &Lit) -> syn::Result<TokenStream2> {
+    let value = string(literal)?;
+    Ok(quote!(::whoopsie::macros::duration(#value)?))
+}
+
+fn output(output: &ReturnType) -> syn::Result<&Type> {
+    match output {
+        ReturnType::Type(_, output) => Ok(output),
+        ReturnType::Default => Err(Error::new(Span::call_site(), "Function must return a Result")),
+    }
+}
+
+fn clones(inputs: &Punctuated<FnArg, Token![,]>, asynchronous: bool) -> syn::Result<Vec<TokenStream2>> {
+    let mut clones = Vec::new();
+    for input in inputs {
+        match input {
+            FnArg::Receiver(receiver) => {
+                if asynchronous && (receiver.reference.is_none() || receiver.mutability.is_some()) {
+                    return Err(Error::new_spanned(receiver, "Retried async methods only support '&self'"));
+                }
+            }
+            FnArg::Typed(argument) => match argument.pat.as_ref() {
+                Pat::Ident(_) if matches!(argument.ty.as_ref(), Type::Reference(reference) if reference.mutability.is_none()) => {}
+                Pat::Ident(_) if matches!(argument.ty.as_ref(), Type::Reference(_)) => {
+                    if asynchronous {
+                        return Err(Error::new_spanned(argument, "Retried async functions do not support '&mut' arguments"));
+                    }
+                }
+                Pat::Ident(pattern) => {
+                    let ident = &pattern.ident;
+                    let mutability = &pattern.mutability;
+                    clones.push(quote!(let #mutability #ident = ::std::clone::Clone::clone(&#ident);));
+                }
+                pattern => return Err(Error::new_spanned(pattern, "Retried functions only support named arguments")),
+            },
+        }
+    }
+    Ok(clones)
+}